@@ -7,6 +7,7 @@ pub(crate) struct FiniteStateMachine {
 }
 
 #[derive(Debug)]
+#[allow(dead_code)]
 pub(crate) struct State {
     pub(crate) id: usize,
     pub(crate) position: egui::Pos2,
@@ -45,6 +46,7 @@ impl State {
 }
 
 #[derive(Debug)]
+#[allow(dead_code)]
 pub(crate) struct Transition {
     pub(crate) from: usize,
     pub(crate) to: usize,
@@ -59,7 +61,7 @@ impl Transition {
         let to_state = fsm.states.get(self.to).unwrap();
 
         let arrow_color = egui::Color32::from_rgb(50, 50, 50);
-        let text_color = egui::Color32::from_rgb(0, 0, 0);
+        let _text_color = egui::Color32::from_rgb(0, 0, 0);
 
         let stroke = egui::Stroke::new(2.0, arrow_color);
         ui.painter().arrow(
@@ -0,0 +1,51 @@
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use fsm::dfa::Dfa;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+/// DFA over the 26 lowercase letters accepting the words whose number of
+/// each vowel is divisible by 3, with 3^5 states.
+fn vowel_counter() -> Dfa<char> {
+    let vowels = ['a', 'e', 'i', 'o', 'u'];
+    let mut dfa = Dfa::new();
+    let states: Vec<_> = (0..243).map(|i| dfa.add_state(i == 0)).collect();
+    for (i, &state) in states.iter().enumerate() {
+        for symbol in 'a'..='z' {
+            let next = match vowels.iter().position(|&v| v == symbol) {
+                Some(v) => {
+                    let weight = 3usize.pow(v as u32);
+                    let digit = i / weight % 3;
+                    i - digit * weight + (digit + 1) % 3 * weight
+                }
+                None => i,
+            };
+            dfa.add_transition(state, symbol, states[next]);
+        }
+    }
+    dfa
+}
+
+fn bench_accepts(c: &mut Criterion) {
+    let dfa = vowel_counter();
+    let dense = dfa.to_dense();
+    let mut rng = StdRng::seed_from_u64(0);
+    let word: Vec<char> = (0..10_000)
+        .map(|_| rng.gen_range(b'a'..=b'z') as char)
+        .collect();
+    assert_eq!(
+        dfa.accepts(word.iter().copied()),
+        dense.accepts(word.iter().copied())
+    );
+
+    let mut group = c.benchmark_group("accepts");
+    group.bench_function("Dfa", |b| {
+        b.iter(|| dfa.accepts(black_box(&word).iter().copied()))
+    });
+    group.bench_function("DenseDfa", |b| {
+        b.iter(|| dense.accepts(black_box(&word).iter().copied()))
+    });
+    group.finish();
+}
+
+criterion_group!(benches, bench_accepts);
+criterion_main!(benches);
@@ -10,10 +10,10 @@ fn main() -> Result<(), ()> {
     mealy.add_transition(b, "0", b, "03");
     mealy.add_transition(b, "1", a, "01");
     mealy.add_transition(b, "2", a, "02");
-    println!("mealy = {:?}", mealy);
-    let mut current = 0;
-    let (next, out) = mealy.next(current,"0").unwrap();
+    print!("{}", mealy.render_graphviz());
+    let current = a;
+    let (next, out) = mealy.next(current, "0").unwrap();
     println!("newstate: {}, output: {}", next, out);
 
     Ok(())
-}
\ No newline at end of file
+}
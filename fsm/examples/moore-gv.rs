@@ -24,9 +24,9 @@ fn main() -> Result<(), ()> {
     moore.add_transition(d, "l1=1", d);
     moore.add_transition(d, "l2=0", d);
     moore.add_transition(d, "l2=1", c);
-    println!("moore = {:?}", moore);
-    let mut current = 0;
-    let (next, out) = moore.next(current,"click").unwrap();
+    print!("{}", moore.render_graphviz());
+    let current = a;
+    let (next, out) = moore.next(current, "click").unwrap();
     println!("newstate: {}, output: {}", next, out);
 
     Ok(())
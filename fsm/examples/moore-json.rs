@@ -1,5 +1,4 @@
 use fsm::moore::Moore;
-use serde::{Deserialize, Serialize};
 
 fn main() -> Result<(), ()> {
     let mut moore = Moore::new();
@@ -26,12 +25,12 @@ fn main() -> Result<(), ()> {
     moore.add_transition(d, "l2=0", d);
     moore.add_transition(d, "l2=1", c);
     println!("moore = {:?}", moore);
-    let mut current = 0;
-    let (next, out) = moore.next(current,"click").unwrap();
+    let current = a;
+    let (next, out) = moore.next(current, "click").unwrap();
     println!("newstate: {}, output: {}", next, out);
     let json_moore = serde_json::to_string_pretty(&moore).unwrap();
     println!("Json: {}", json_moore);
-    let moore_json: Moore<&str,&str> = serde_json::from_str(&json_moore).unwrap();
+    let moore_json: Moore<&str, &str> = serde_json::from_str(&json_moore).unwrap();
     println!("moore_json = {:?}", moore_json);
     let json_moore1 = serde_json::to_string_pretty(&moore_json).unwrap();
     println!("Json1: {}", json_moore1);
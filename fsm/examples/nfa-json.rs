@@ -32,7 +32,7 @@ fn main() -> color_eyre::Result<()> {
     ]}";
     println!("json3 = {}", json3);
 
-    let nfa3: Nfa<char> = serde_json::from_str(&json3)?;
+    let nfa3: Nfa<char> = serde_json::from_str(json3)?;
     println!("nfa3 = {:?}", nfa3);
     println!("{}", nfa3.render_graphviz());
 
@@ -0,0 +1,242 @@
+use std::collections::{BTreeSet, HashMap};
+use std::fmt::{Debug, Display};
+use std::hash::Hash;
+
+use crate::alphabet::Alphabet;
+use crate::util::dfs::multi_dfs;
+
+/// Structure shared by all machines: a set of states, some of them initial,
+/// connected by transitions labelled with inputs or ε.
+pub trait Automaton {
+    type StateId: Copy + Ord + Hash + Debug + Display;
+    type Input: Alphabet;
+
+    fn num_states(&self) -> usize;
+
+    fn state_ids(&self) -> impl Iterator<Item = Self::StateId> + '_;
+
+    /// Returns the states a run may start from.
+    fn initial_state_ids(&self) -> impl Iterator<Item = Self::StateId> + '_;
+
+    /// Returns the outgoing transitions of `state`, where `None` stands for ε.
+    fn successors(
+        &self,
+        state: Self::StateId,
+    ) -> impl Iterator<Item = (Option<Self::Input>, Self::StateId)> + '_;
+
+    /// Returns the states reachable from the initial states, following both
+    /// input and ε-transitions.
+    fn reachable_states(&self) -> BTreeSet<Self::StateId> {
+        multi_dfs(self.initial_state_ids().collect(), |s| {
+            self.successors(s).map(|(_, to)| to).collect::<Vec<_>>()
+        })
+        .collect()
+    }
+
+    fn statistics(&self) -> Statistics {
+        let mut statistics = Statistics {
+            states: self.num_states(),
+            initial: self.initial_state_ids().count(),
+            reachable: self.reachable_states().len(),
+            ..Statistics::default()
+        };
+        for state in self.state_ids() {
+            for (input, _) in self.successors(state) {
+                match input {
+                    Some(_) => statistics.transitions += 1,
+                    None => statistics.epsilon_transitions += 1,
+                }
+            }
+        }
+        statistics
+    }
+}
+
+/// Automaton deciding membership of words through its accepting states.
+pub trait Acceptor: Automaton {
+    fn is_accepting(&self, state: Self::StateId) -> bool;
+
+    fn accepts(&self, word: impl IntoIterator<Item = Self::Input>) -> bool;
+
+    /// Returns the states from which some accepting state is reachable,
+    /// following both input and ε-transitions.
+    fn coreachable_states(&self) -> BTreeSet<Self::StateId> {
+        let mut predecessors: HashMap<Self::StateId, Vec<Self::StateId>> = HashMap::new();
+        for from in self.state_ids() {
+            for (_, to) in self.successors(from) {
+                predecessors.entry(to).or_default().push(from);
+            }
+        }
+        let accepting = self.state_ids().filter(|&s| self.is_accepting(s)).collect();
+        multi_dfs(accepting, |s| {
+            predecessors.get(&s).into_iter().flatten().copied()
+        })
+        .collect()
+    }
+
+    /// Returns the states that are both reachable and co-reachable, i.e.
+    /// those lying on some accepting run.
+    fn useful_states(&self) -> BTreeSet<Self::StateId> {
+        let coreachable = self.coreachable_states();
+        self.reachable_states()
+            .intersection(&coreachable)
+            .copied()
+            .collect()
+    }
+}
+
+/// Acceptor that can be rebuilt state by state, see [`trim`].
+pub(crate) trait Build: Acceptor + Default {
+    /// Adds a state with the acceptance of `state` of `source`, but none of
+    /// its transitions.
+    fn add_state_like(&mut self, source: &Self, state: Self::StateId) -> Self::StateId;
+
+    /// Adds a transition, where `None` stands for ε.
+    fn add_successor(&mut self, from: Self::StateId, input: Option<Self::Input>, to: Self::StateId);
+
+    fn set_initial_state_ids(&mut self, states: impl IntoIterator<Item = Self::StateId>);
+}
+
+/// Returns a copy of `machine` containing only its useful states and its
+/// initial states, renumbered in increasing order of their old ids.
+pub(crate) fn trim<M: Build>(machine: &M) -> M {
+    let mut trimmed = M::default();
+    let mut kept = machine.useful_states();
+    kept.extend(machine.initial_state_ids());
+    let old2new: HashMap<M::StateId, M::StateId> = kept
+        .iter()
+        .map(|&s| (s, trimmed.add_state_like(machine, s)))
+        .collect();
+    trimmed.set_initial_state_ids(machine.initial_state_ids().map(|s| old2new[&s]));
+    for &from in &kept {
+        for (input, to) in machine.successors(from) {
+            if let Some(&new_to) = old2new.get(&to) {
+                trimmed.add_successor(old2new[&from], input, new_to);
+            }
+        }
+    }
+    trimmed
+}
+
+/// Deterministic automaton producing an output on every transition.
+pub trait Transducer: Automaton {
+    type Output: Alphabet;
+
+    /// Returns the state reached from `state` on `input` and the output
+    /// produced, or `None` if there is no such transition.
+    fn step(
+        &self,
+        state: Self::StateId,
+        input: Self::Input,
+    ) -> Option<(Self::StateId, Self::Output)>;
+
+    /// Feeds `inputs` to the machine from its initial state and collects the
+    /// outputs, or returns `None` if there is no initial state or the machine
+    /// gets stuck.
+    fn transduce(
+        &self,
+        inputs: impl IntoIterator<Item = Self::Input>,
+    ) -> Option<Vec<Self::Output>> {
+        let mut state = self.initial_state_ids().next()?;
+        inputs
+            .into_iter()
+            .map(|input| {
+                let (next, output) = self.step(state, input)?;
+                state = next;
+                Some(output)
+            })
+            .collect()
+    }
+}
+
+/// Size summary of an automaton, see [`Automaton::statistics`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Statistics {
+    pub states: usize,
+    pub initial: usize,
+    pub reachable: usize,
+    pub transitions: usize,
+    pub epsilon_transitions: usize,
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::dfa::Dfa;
+    use crate::mealy::Mealy;
+    use crate::moore::Moore;
+    use crate::nfa::Nfa;
+
+    use super::*;
+
+    fn useful<M: Acceptor>(machine: &M) -> BTreeSet<M::StateId> {
+        machine.useful_states()
+    }
+
+    #[test]
+    fn test_generic_reachability() {
+        let mut dfa = Dfa::new();
+        let a = dfa.add_state(false);
+        let b = dfa.add_state(true);
+        let unreachable = dfa.add_state(true);
+        let trap = dfa.add_state(false);
+        dfa.add_transition(a, 'x', b);
+        dfa.add_transition(b, 'y', trap);
+        dfa.add_transition(unreachable, 'x', a);
+        assert_eq!(useful(&dfa), BTreeSet::from([a, b]));
+
+        let mut nfa = Nfa::new();
+        let a = nfa.add_state(false);
+        let b = nfa.add_state(false);
+        let c = nfa.add_state(true);
+        nfa.add_epsilon_transition(a, b);
+        nfa.add_transition(b, 'x', c);
+        nfa.add_transition(c, 'x', a);
+        assert_eq!(useful(&nfa), BTreeSet::from([a, b, c]));
+        assert!(Acceptor::accepts(&nfa, ['x']));
+        assert!(!Acceptor::accepts(&nfa, ['x', 'x']));
+    }
+
+    #[test]
+    fn test_statistics() {
+        let mut nfa = Nfa::new();
+        let a = nfa.add_state(false);
+        let b = nfa.add_state(true);
+        let c = nfa.add_state(false);
+        nfa.add_initial(b);
+        nfa.add_transition(a, 'x', b);
+        nfa.add_transition(a, 'y', b);
+        nfa.add_epsilon_transition(b, a);
+        nfa.add_transition(c, 'x', a);
+        assert_eq!(
+            nfa.statistics(),
+            Statistics {
+                states: 3,
+                initial: 2,
+                reachable: 2,
+                transitions: 3,
+                epsilon_transitions: 1,
+            }
+        );
+        assert_eq!(Dfa::<char>::new().statistics(), Statistics::default());
+    }
+
+    #[test]
+    fn test_transduce() {
+        let mut mealy = Mealy::new();
+        let a = mealy.add_state();
+        let b = mealy.add_state();
+        mealy.add_transition(a, 'x', b, 1);
+        mealy.add_transition(b, 'x', a, 2);
+        assert_eq!(mealy.transduce("xxx".chars()), Some(vec![1, 2, 1]));
+        assert_eq!(mealy.transduce("xy".chars()), None);
+
+        let mut moore = Moore::new();
+        let a = moore.add_state(0);
+        let b = moore.add_state(1);
+        moore.add_transition(a, 'x', b);
+        moore.add_transition(b, 'x', a);
+        assert_eq!(moore.transduce("xxx".chars()), Some(vec![1, 0, 1]));
+        assert_eq!(Moore::<char, u8>::new().transduce([]), None);
+        assert_eq!(moore.reachable_states(), BTreeSet::from([a, b]));
+    }
+}
@@ -0,0 +1,101 @@
+use crate::alphabet::Alphabet;
+use crate::dfa::Dfa;
+
+impl<A: Alphabet> Dfa<A> {
+    /// Returns an equivalent DFA in which every state has a transition on
+    /// every symbol of `alphabet`.
+    ///
+    /// Missing transitions are redirected into a fresh non-accepting sink
+    /// state, which loops on every symbol. The sink is only added when some
+    /// transition is missing (or the DFA has no states at all, in which case
+    /// the sink becomes the initial state).
+    pub fn complete(&self, alphabet: &[A]) -> Dfa<A> {
+        let mut dfa = self.clone();
+        let is_complete = !dfa.states.is_empty()
+            && dfa
+                .states()
+                .all(|state| alphabet.iter().all(|&symbol| state.next(symbol).is_some()));
+        if is_complete {
+            return dfa;
+        }
+
+        let sink = dfa.add_state(false);
+        for state in dfa.states.iter_mut() {
+            for &symbol in alphabet {
+                if state.next(symbol).is_none() {
+                    state.add_transition(symbol, sink);
+                }
+            }
+        }
+        dfa
+    }
+
+    /// Returns a DFA recognizing the complement of the language with respect
+    /// to `alphabet*`, by completing the DFA and flipping acceptance.
+    pub fn complement(&self, alphabet: &[A]) -> Dfa<A> {
+        let mut dfa = self.complete(alphabet);
+        for state in dfa.states.iter_mut() {
+            state.accepting = !state.accepting;
+        }
+        dfa
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::test_common::generate_words;
+
+    use super::*;
+
+    #[test]
+    fn test_complete() {
+        let mut dfa = Dfa::new();
+        // Words starting with 'a'
+        let q0 = dfa.add_state(false);
+        let q1 = dfa.add_state(true);
+        dfa.add_transition(q0, 'a', q1);
+        dfa.add_transition(q1, 'a', q1);
+        dfa.add_transition(q1, 'b', q1);
+
+        let complete = dfa.complete(&['a', 'b']);
+        assert_eq!(complete.num_states(), 3);
+        assert_eq!(complete.num_transitions(), 6);
+        for word in generate_words(&['a', 'b'], 8) {
+            assert_eq!(dfa.accepts(word.clone()), complete.accepts(word));
+        }
+
+        // Completing an already complete DFA does not add a sink
+        assert_eq!(complete.complete(&['a', 'b']).num_states(), 3);
+    }
+
+    #[test]
+    fn test_complement() {
+        let mut dfa = Dfa::new();
+        // Words containing "ab"
+        let q0 = dfa.add_state(false);
+        let q1 = dfa.add_state(false);
+        let q2 = dfa.add_state(true);
+        dfa.add_transition(q0, 'a', q1);
+        dfa.add_transition(q0, 'b', q0);
+        dfa.add_transition(q1, 'a', q1);
+        dfa.add_transition(q1, 'b', q2);
+        dfa.add_transition(q2, 'a', q2);
+        dfa.add_transition(q2, 'b', q2);
+
+        let complement = dfa.complement(&['a', 'b', 'c']);
+        for word in generate_words(&['a', 'b', 'c'], 6) {
+            assert_eq!(dfa.accepts(word.clone()), !complement.accepts(word));
+        }
+        assert_eq!(complement.complement(&['a', 'b', 'c']).num_states(), 4);
+    }
+
+    #[test]
+    fn test_complement_empty() {
+        let dfa = Dfa::new();
+        let universal = dfa.complement(&['a', 'b']);
+        assert_eq!(universal.num_states(), 1);
+        for word in generate_words(&['a', 'b'], 6) {
+            assert!(universal.accepts(word));
+        }
+    }
+}
@@ -0,0 +1,193 @@
+use std::collections::{BTreeSet, HashMap};
+
+use rand::Rng;
+
+use crate::alphabet::Alphabet;
+use crate::dfa::state::StateId;
+use crate::dfa::Dfa;
+
+impl<A: Alphabet> Dfa<A> {
+    /// Returns the number of accepted words of length exactly `len`, or
+    /// `None` if it does not fit into `u128`.
+    pub fn count_words(&self, len: usize) -> Option<u128> {
+        let Some(initial) = self.initial else {
+            return Some(0);
+        };
+        self.count_table(len)[len]
+            .get(&initial)
+            .copied()
+            .unwrap_or(Some(0))
+    }
+
+    /// Draws a word of length `len` uniformly at random among all accepted
+    /// words of that length, or returns `None` if there are none.
+    ///
+    /// Each symbol is chosen with probability proportional to the number of
+    /// accepted completions it leaves, as given by [`Dfa::count_words`].
+    /// Also returns `None` if that number does not fit into `u128`.
+    pub fn sample_word<R: Rng + ?Sized>(&self, len: usize, rng: &mut R) -> Option<Vec<A>> {
+        let initial = self.initial?;
+        let counts = self.count_table(len);
+        let total = counts[len].get(&initial).copied().unwrap_or(Some(0))?;
+        if total == 0 {
+            return None;
+        }
+
+        let mut word = Vec::with_capacity(len);
+        let mut state = initial;
+        for remaining in (0..len).rev() {
+            // Counts along the path fit, since each is at most the total.
+            let mut r = rng.gen_range(0..counts[remaining + 1][&state].unwrap());
+            let transitions: BTreeSet<(A, StateId)> = self.state(state).transitions().collect();
+            for (symbol, to) in transitions {
+                let count = counts[remaining].get(&to).map_or(0, |count| count.unwrap());
+                if r < count {
+                    word.push(symbol);
+                    state = to;
+                    break;
+                }
+                r -= count;
+            }
+        }
+        Some(word)
+    }
+
+    /// `table[k][s]` is the number of words of length `k` accepted from state `s`,
+    /// or `None` if it does not fit into `u128`. States without any such word
+    /// are omitted. Overflow is tracked per entry, so that it only matters for
+    /// the counts a caller actually reads.
+    fn count_table(&self, len: usize) -> Vec<HashMap<StateId, Option<u128>>> {
+        let mut table: Vec<HashMap<StateId, Option<u128>>> = Vec::with_capacity(len + 1);
+        table.push(
+            self.states()
+                .filter(|state| state.accepting)
+                .map(|state| (state.id, Some(1)))
+                .collect(),
+        );
+        for k in 1..=len {
+            let mut counts: HashMap<StateId, Option<u128>> = HashMap::new();
+            for (from, _, to) in self.transitions() {
+                if let Some(&count) = table[k - 1].get(&to.id) {
+                    let total = counts.entry(from.id).or_insert(Some(0));
+                    *total = total.zip(count).and_then(|(a, b)| a.checked_add(b));
+                }
+            }
+            table.push(counts);
+        }
+        table
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    use crate::test_common::generate_words;
+
+    use super::*;
+
+    /// Words over {a, b} not containing "aa".
+    fn no_aa() -> Dfa<char> {
+        let mut dfa = Dfa::new();
+        let q0 = dfa.add_state(true);
+        let q1 = dfa.add_state(true);
+        dfa.add_transition(q0, 'a', q1);
+        dfa.add_transition(q0, 'b', q0);
+        dfa.add_transition(q1, 'b', q0);
+        dfa
+    }
+
+    #[test]
+    fn test_count_words() {
+        let dfa = no_aa();
+        // Fibonacci numbers
+        let counts = (0..10)
+            .map(|len| dfa.count_words(len).unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(counts, vec![1, 2, 3, 5, 8, 13, 21, 34, 55, 89]);
+        for len in 0..8 {
+            let expected = generate_words(&['a', 'b'], len)
+                .into_iter()
+                .filter(|word| word.len() == len && dfa.accepts(word.clone()))
+                .count();
+            assert_eq!(dfa.count_words(len), Some(expected as u128));
+        }
+        assert_eq!(Dfa::<char>::new().count_words(3), Some(0));
+    }
+
+    #[test]
+    fn test_count_words_large() {
+        let mut dfa = Dfa::new();
+        let q = dfa.add_state(true);
+        dfa.add_transition(q, 0u8, q);
+        dfa.add_transition(q, 1u8, q);
+        assert_eq!(dfa.count_words(127), Some(1 << 127));
+        assert_eq!(dfa.count_words(128), None);
+
+        // All words over 3 symbols overflow around length 81
+        dfa.add_transition(q, 2u8, q);
+        assert!(dfa.count_words(80).is_some());
+        assert_eq!(dfa.count_words(81), None);
+        let mut rng = StdRng::seed_from_u64(0);
+        assert_eq!(dfa.sample_word(81, &mut rng), None);
+        assert_eq!(
+            dfa.sample_word(80, &mut rng).map(|word| word.len()),
+            Some(80)
+        );
+    }
+
+    #[test]
+    fn test_count_words_unreachable_overflow() {
+        // An unreachable component accepting every word over 256 symbols
+        // overflows long before the reachable one does.
+        let mut dfa = Dfa::new();
+        let q0 = dfa.add_state(false);
+        let q1 = dfa.add_state(true);
+        dfa.add_transition(q0, 0u8, q1);
+        dfa.add_transition(q1, 0u8, q0);
+        let sink = dfa.add_state(true);
+        for symbol in 0..=u8::MAX {
+            dfa.add_transition(sink, symbol, sink);
+        }
+        assert_eq!(dfa.count_words(21), Some(1));
+        assert_eq!(dfa.count_words(22), Some(0));
+        let mut rng = StdRng::seed_from_u64(0);
+        assert_eq!(dfa.sample_word(21, &mut rng), Some(vec![0; 21]));
+    }
+
+    #[test]
+    fn test_sample_word() {
+        let dfa = no_aa();
+        let mut rng = StdRng::seed_from_u64(42);
+        assert_eq!(Dfa::<char>::new().sample_word(3, &mut rng), None);
+        assert_eq!(dfa.sample_word(0, &mut rng), Some(vec![]));
+
+        // All 8 accepted words of length 4 are drawn with roughly equal frequency
+        let mut histogram: HashMap<Vec<char>, usize> = HashMap::new();
+        for _ in 0..8000 {
+            let word = dfa.sample_word(4, &mut rng).unwrap();
+            assert!(dfa.accepts(word.clone()));
+            *histogram.entry(word).or_default() += 1;
+        }
+        assert_eq!(histogram.len(), 8);
+        for &count in histogram.values() {
+            assert!((800..1200).contains(&count), "count = {}", count);
+        }
+    }
+
+    #[test]
+    fn test_sample_word_none() {
+        let mut dfa = Dfa::new();
+        // Only even-length words
+        let q0 = dfa.add_state(true);
+        let q1 = dfa.add_state(false);
+        dfa.add_transition(q0, 'x', q1);
+        dfa.add_transition(q1, 'x', q0);
+        let mut rng = StdRng::seed_from_u64(0);
+        assert_eq!(dfa.sample_word(3, &mut rng), None);
+        assert_eq!(dfa.sample_word(4, &mut rng), Some(vec!['x'; 4]));
+    }
+}
@@ -0,0 +1,288 @@
+use std::collections::{BTreeSet, HashMap, HashSet, VecDeque};
+
+use crate::alphabet::Alphabet;
+use crate::automaton::Acceptor;
+use crate::dfa::state::StateId;
+use crate::dfa::Dfa;
+
+/// Witness of an infinite language: `prefix · cycleⁿ · suffix` is accepted for every `n ≥ 0`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PumpingWitness<A> {
+    pub prefix: Vec<A>,
+    pub cycle: Vec<A>,
+    pub suffix: Vec<A>,
+}
+
+impl<A: Alphabet> Dfa<A> {
+    /// Returns whether the DFA accepts no word at all. Otherwise,
+    /// [`Dfa::shortest_word`] returns the shortest accepted word.
+    pub fn is_empty(&self) -> bool {
+        self.shortest_word().is_none()
+    }
+
+    /// Returns whether the DFA accepts every word over `alphabet`.
+    pub fn is_universal(&self, alphabet: &[A]) -> bool {
+        self.universality_counterexample(alphabet).is_none()
+    }
+
+    /// Returns the shortest word over `alphabet` rejected by the DFA, or
+    /// `None` if it accepts them all.
+    pub fn universality_counterexample(&self, alphabet: &[A]) -> Option<Vec<A>> {
+        self.complement(alphabet).shortest_word()
+    }
+
+    /// Returns whether every word accepted by `self` is also accepted by
+    /// `other`.
+    pub fn is_subset_of(&self, other: &Dfa<A>) -> bool {
+        self.subset_counterexample(other).is_none()
+    }
+
+    /// Returns the shortest word accepted by `self` but not by `other`, or
+    /// `None` if there is none.
+    pub fn subset_counterexample(&self, other: &Dfa<A>) -> Option<Vec<A>> {
+        self.difference(other).shortest_word()
+    }
+
+    /// Returns whether the DFA accepts finitely many words.
+    pub fn is_finite(&self) -> bool {
+        self.pumping_witness().is_none()
+    }
+
+    /// Returns a pumpable decomposition of an accepted word, or `None` if
+    /// the DFA accepts finitely many words.
+    ///
+    /// The language is infinite iff some cycle passes through a state that
+    /// is both reachable from the initial state and co-reachable from an
+    /// accepting state.
+    pub fn pumping_witness(&self) -> Option<PumpingWitness<A>> {
+        let initial = self.initial?;
+
+        let useful = self.useful_states();
+        if !useful.contains(&initial) {
+            return None;
+        }
+
+        let useful_transitions = |s: StateId| {
+            let mut transitions: Vec<(A, StateId)> = self
+                .state(s)
+                .transitions()
+                .filter(|(_, to)| useful.contains(to))
+                .collect();
+            transitions.sort_unstable();
+            transitions
+        };
+
+        // Iterative DFS over useful states, looking for a back edge.
+        // `path[i]` is the symbol leading from `stack[i]` to `stack[i + 1]`.
+        let mut stack = vec![(initial, useful_transitions(initial), 0)];
+        let mut path = Vec::new();
+        let mut on_stack = HashMap::from([(initial, 0)]);
+        let mut visited = HashSet::from([initial]);
+        while let Some((state, transitions, next)) = stack.last_mut() {
+            if let Some(&(symbol, to)) = transitions.get(*next) {
+                *next += 1;
+                if let Some(&position) = on_stack.get(&to) {
+                    let mut cycle = path[position..].to_vec();
+                    cycle.push(symbol);
+                    let suffix = self
+                        .shortest_path(to, |s| self.accepting(s))
+                        .expect("useful states are co-reachable");
+                    return Some(PumpingWitness {
+                        prefix: path[..position].to_vec(),
+                        cycle,
+                        suffix,
+                    });
+                }
+                if visited.insert(to) {
+                    path.push(symbol);
+                    on_stack.insert(to, stack.len());
+                    stack.push((to, useful_transitions(to), 0));
+                }
+            } else {
+                on_stack.remove(state);
+                stack.pop();
+                path.pop();
+            }
+        }
+        None
+    }
+
+    /// Breadth-first search for the shortlex-smallest word leading from
+    /// `from` to a state satisfying `target`.
+    pub(super) fn shortest_path(
+        &self,
+        from: StateId,
+        target: impl Fn(StateId) -> bool,
+    ) -> Option<Vec<A>> {
+        let mut parent: HashMap<StateId, (StateId, A)> = HashMap::new();
+        let mut queue = VecDeque::from([from]);
+        while let Some(state) = queue.pop_front() {
+            if target(state) {
+                let mut word = Vec::new();
+                let mut current = state;
+                while let Some(&(previous, symbol)) = parent.get(&current) {
+                    word.push(symbol);
+                    current = previous;
+                }
+                word.reverse();
+                return Some(word);
+            }
+            let transitions: BTreeSet<(A, StateId)> = self.state(state).transitions().collect();
+            for (symbol, to) in transitions {
+                if to != from && !parent.contains_key(&to) {
+                    parent.insert(to, (state, symbol));
+                    queue.push_back(to);
+                }
+            }
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::iter::repeat_n;
+
+    use crate::test_common::generate_words;
+
+    use super::*;
+
+    /// Words over {a, b} containing "ab", over a partial transition function
+    /// with an extra useless state.
+    fn contains_ab() -> Dfa<char> {
+        let mut dfa = Dfa::new();
+        let q0 = dfa.add_state(false);
+        let q1 = dfa.add_state(false);
+        let q2 = dfa.add_state(true);
+        let trap = dfa.add_state(false);
+        dfa.add_transition(q0, 'a', q1);
+        dfa.add_transition(q0, 'b', q0);
+        dfa.add_transition(q0, 'c', trap);
+        dfa.add_transition(trap, 'c', trap);
+        dfa.add_transition(q1, 'a', q1);
+        dfa.add_transition(q1, 'b', q2);
+        dfa.add_transition(q2, 'a', q2);
+        dfa.add_transition(q2, 'b', q2);
+        dfa
+    }
+
+    #[test]
+    fn test_is_empty() {
+        assert!(!contains_ab().is_empty());
+        assert!(Dfa::<char>::new().is_empty());
+
+        let mut dfa = Dfa::new();
+        let q0 = dfa.add_state(false);
+        let q1 = dfa.add_state(false);
+        let q2 = dfa.add_state(true);
+        dfa.add_transition(q0, 'a', q1);
+        dfa.add_transition(q1, 'a', q0);
+        assert!(dfa.is_empty());
+        dfa.add_transition(q1, 'b', q2);
+        assert!(!dfa.is_empty());
+        assert_eq!(dfa.shortest_word(), Some(vec!['a', 'b']));
+    }
+
+    #[test]
+    fn test_is_universal() {
+        assert!(!contains_ab().is_universal(&['a', 'b']));
+        assert_eq!(
+            contains_ab().universality_counterexample(&['a', 'b']),
+            Some(vec![])
+        );
+
+        let mut dfa = Dfa::new();
+        let q0 = dfa.add_state(true);
+        let q1 = dfa.add_state(true);
+        dfa.add_transition(q0, 'a', q1);
+        dfa.add_transition(q1, 'a', q0);
+        dfa.add_transition(q0, 'b', q0);
+        assert_eq!(
+            dfa.universality_counterexample(&['a', 'b']),
+            Some(vec!['a', 'b'])
+        );
+        dfa.add_transition(q1, 'b', q1);
+        assert!(dfa.is_universal(&['a', 'b']));
+        assert_eq!(dfa.universality_counterexample(&['a', 'b']), None);
+        assert!(!dfa.is_universal(&['a', 'b', 'c']));
+        assert_eq!(
+            dfa.universality_counterexample(&['a', 'b', 'c']),
+            Some(vec!['c'])
+        );
+    }
+
+    #[test]
+    fn test_is_subset_of() {
+        let mut ends_with_ab = Dfa::new();
+        let q0 = ends_with_ab.add_state(false);
+        let q1 = ends_with_ab.add_state(false);
+        let q2 = ends_with_ab.add_state(true);
+        ends_with_ab.add_transition(q0, 'a', q1);
+        ends_with_ab.add_transition(q0, 'b', q0);
+        ends_with_ab.add_transition(q1, 'a', q1);
+        ends_with_ab.add_transition(q1, 'b', q2);
+        ends_with_ab.add_transition(q2, 'a', q1);
+        ends_with_ab.add_transition(q2, 'b', q0);
+
+        assert!(ends_with_ab.is_subset_of(&contains_ab()));
+        assert_eq!(ends_with_ab.subset_counterexample(&contains_ab()), None);
+        assert!(!contains_ab().is_subset_of(&ends_with_ab));
+        assert_eq!(
+            contains_ab().subset_counterexample(&ends_with_ab),
+            Some(vec!['a', 'b', 'a'])
+        );
+    }
+
+    #[test]
+    fn test_is_finite() {
+        let mut dfa = Dfa::new();
+        // Accepts "ab" and "b" only; the 'c' cycle is not co-reachable
+        let q0 = dfa.add_state(false);
+        let q1 = dfa.add_state(false);
+        let q2 = dfa.add_state(true);
+        let trap = dfa.add_state(false);
+        dfa.add_transition(q0, 'a', q1);
+        dfa.add_transition(q0, 'b', q2);
+        dfa.add_transition(q1, 'b', q2);
+        dfa.add_transition(q1, 'c', trap);
+        dfa.add_transition(trap, 'c', trap);
+        assert!(dfa.is_finite());
+        assert_eq!(dfa.pumping_witness(), None);
+        assert!(Dfa::<char>::new().is_finite());
+
+        assert!(!contains_ab().is_finite());
+        let witness = contains_ab().pumping_witness().unwrap();
+        assert!(!witness.cycle.is_empty());
+        for n in 0..5 {
+            let word = witness
+                .prefix
+                .iter()
+                .chain(repeat_n(&witness.cycle, n).flatten())
+                .chain(&witness.suffix)
+                .copied()
+                .collect::<Vec<_>>();
+            assert!(contains_ab().accepts(word));
+        }
+    }
+
+    #[test]
+    fn test_is_finite_intersection() {
+        let dfa = contains_ab();
+        let finite = dfa.intersect(&{
+            // Words of length at most 3
+            let mut short = Dfa::new();
+            let states = (0..4).map(|_| short.add_state(true)).collect::<Vec<_>>();
+            for i in 0..3 {
+                short.add_transition(states[i], 'a', states[i + 1]);
+                short.add_transition(states[i], 'b', states[i + 1]);
+            }
+            short
+        });
+        assert!(finite.is_finite());
+        let accepted = generate_words(&['a', 'b'], 5)
+            .into_iter()
+            .filter(|word| finite.accepts(word.clone()))
+            .count();
+        assert_eq!(accepted, 5);
+    }
+}
@@ -0,0 +1,221 @@
+use crate::alphabet::Alphabet;
+use crate::automaton::{Acceptor, Automaton};
+use crate::dfa::state::StateId;
+use crate::dfa::Dfa;
+
+/// Sentinel stored in the table for missing transitions.
+const DEAD: StateId = StateId::new(usize::MAX);
+
+/// Table-driven DFA compiled from a [`Dfa`] for fast matching.
+///
+/// The alphabet is mapped to contiguous column indices, and transitions are
+/// stored in a flat row-major table with one row per state id, so a step is
+/// a column lookup followed by a single index. State ids are the ones of the
+/// original DFA; rows of removed states are dead and not counted as states.
+#[derive(Debug, Clone)]
+pub struct DenseDfa<A: Alphabet> {
+    /// Sorted, so that the column of a symbol is its position.
+    alphabet: Vec<A>,
+    table: Vec<StateId>,
+    accepting: Vec<bool>,
+    /// Ids of the rows of live states, in increasing order.
+    live: Vec<StateId>,
+    initial: Option<StateId>,
+}
+
+impl<A: Alphabet> Dfa<A> {
+    /// Compiles the DFA into a [`DenseDfa`] over the symbols it has
+    /// transitions on.
+    pub fn to_dense(&self) -> DenseDfa<A> {
+        let mut alphabet: Vec<A> = self.transitions().map(|(_, symbol, _)| symbol).collect();
+        alphabet.sort_unstable();
+        alphabet.dedup();
+
+        let rows = self.states.id_bound();
+        let mut table = vec![DEAD; rows * alphabet.len()];
+        let mut accepting = vec![false; rows];
+        let mut live = Vec::with_capacity(self.num_states());
+        for state in self.states() {
+            live.push(state.id);
+            let row = state.id.index() * alphabet.len();
+            accepting[state.id.index()] = state.accepting;
+            for (symbol, to) in state.transitions() {
+                let column = alphabet.binary_search(&symbol).unwrap();
+                table[row + column] = to;
+            }
+        }
+
+        DenseDfa {
+            alphabet,
+            table,
+            accepting,
+            live,
+            initial: self.initial,
+        }
+    }
+}
+
+impl<A: Alphabet> DenseDfa<A> {
+    pub fn initial(&self) -> Option<StateId> {
+        self.initial
+    }
+
+    /// Returns the symbols the table has columns for, in increasing order.
+    pub fn alphabet(&self) -> &[A] {
+        &self.alphabet
+    }
+
+    /// Returns the number of states, not counting the dead rows of removed
+    /// states.
+    pub fn num_states(&self) -> usize {
+        self.live.len()
+    }
+
+    /// Returns the ids of the states in increasing order.
+    pub fn state_ids(&self) -> impl Iterator<Item = StateId> + '_ {
+        self.live.iter().copied()
+    }
+
+    /// Returns whether `state` is accepting, which is never the case for
+    /// ids outside the table or of removed states.
+    pub fn accepting(&self, state: StateId) -> bool {
+        self.accepting.get(state.index()).copied().unwrap_or(false)
+    }
+
+    fn column(&self, symbol: A) -> Option<usize> {
+        self.alphabet.binary_search(&symbol).ok()
+    }
+
+    /// Returns the row of `state`, or `None` if it lies outside the table.
+    fn row(&self, state: StateId) -> Option<&[StateId]> {
+        let width = self.alphabet.len();
+        let start = state.index().checked_mul(width)?;
+        self.table.get(start..start.checked_add(width)?)
+    }
+
+    pub fn next(&self, current_state: StateId, symbol: A) -> Option<StateId> {
+        let column = self.column(symbol)?;
+        let next = self.row(current_state)?[column];
+        (next != DEAD).then_some(next)
+    }
+
+    pub fn accepts(&self, word: impl IntoIterator<Item = A>) -> bool {
+        let Some(mut current_state) = self.initial else {
+            return false;
+        };
+        for symbol in word {
+            if let Some(next_state) = self.next(current_state, symbol) {
+                current_state = next_state;
+            } else {
+                return false;
+            }
+        }
+        self.accepting(current_state)
+    }
+}
+
+impl<A: Alphabet> Automaton for DenseDfa<A> {
+    type StateId = StateId;
+    type Input = A;
+
+    fn num_states(&self) -> usize {
+        self.num_states()
+    }
+
+    fn state_ids(&self) -> impl Iterator<Item = StateId> + '_ {
+        self.state_ids()
+    }
+
+    fn initial_state_ids(&self) -> impl Iterator<Item = StateId> + '_ {
+        self.initial.into_iter()
+    }
+
+    fn successors(&self, state: StateId) -> impl Iterator<Item = (Option<A>, StateId)> + '_ {
+        self.alphabet
+            .iter()
+            .zip(self.row(state).unwrap_or_default())
+            .filter(|&(_, &to)| to != DEAD)
+            .map(|(&symbol, &to)| (Some(symbol), to))
+    }
+}
+
+impl<A: Alphabet> Acceptor for DenseDfa<A> {
+    fn is_accepting(&self, state: StateId) -> bool {
+        self.accepting(state)
+    }
+
+    fn accepts(&self, word: impl IntoIterator<Item = A>) -> bool {
+        self.accepts(word)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::test_common::generate_words;
+
+    use super::*;
+
+    #[test]
+    fn test_dense_agrees_with_dfa() {
+        let mut dfa = Dfa::new();
+        // Words over {a, b} containing "ab", with a removed state in between
+        let q0 = dfa.add_state(false);
+        let removed = dfa.add_state(false);
+        let q1 = dfa.add_state(false);
+        let q2 = dfa.add_state(true);
+        dfa.add_transition(q0, 'a', q1);
+        dfa.add_transition(q0, 'b', q0);
+        dfa.add_transition(q1, 'a', q1);
+        dfa.add_transition(q1, 'b', q2);
+        dfa.add_transition(q2, 'a', q2);
+        dfa.add_transition(q2, 'b', q2);
+        dfa.add_transition(q2, 'c', removed);
+        dfa.remove_state(removed);
+
+        let dense = dfa.to_dense();
+        assert_eq!(dense.alphabet(), &['a', 'b']);
+        assert_eq!(dense.next(q0, 'a'), Some(q1));
+        assert_eq!(dense.next(q0, 'c'), None);
+        assert_eq!(dense.next(removed, 'a'), None);
+        for word in generate_words(&['a', 'b', 'c'], 7) {
+            assert_eq!(dfa.accepts(word.clone()), dense.accepts(word));
+        }
+        assert_eq!(dense.reachable_states(), dfa.reachable_states());
+        assert_eq!(dense.coreachable_states(), dfa.coreachable_states());
+        assert_eq!(dense.num_states(), 3);
+        assert_eq!(dense.state_ids().collect::<Vec<_>>(), vec![q0, q1, q2]);
+        assert_eq!(dense.statistics(), dfa.statistics());
+    }
+
+    #[test]
+    fn test_dense_unknown_state() {
+        let mut dfa = Dfa::new();
+        let a = dfa.add_state(false);
+        let b = dfa.add_state(true);
+        dfa.add_transition(a, 'x', b);
+        dfa.add_transition(b, 'x', b);
+        dfa.remove_state(a);
+
+        let dense = dfa.to_dense();
+        let unknown = StateId::new(7);
+        assert!(!dense.accepting(a));
+        assert!(!dense.accepting(unknown));
+        assert_eq!(dense.next(a, 'x'), None);
+        assert_eq!(dense.next(unknown, 'x'), None);
+        assert_eq!(dense.next(StateId::new(usize::MAX), 'x'), None);
+        assert_eq!(dense.successors(unknown).count(), 0);
+    }
+
+    #[test]
+    fn test_dense_empty() {
+        let dense = Dfa::<char>::new().to_dense();
+        assert_eq!(dense.num_states(), 0);
+        assert!(!dense.accepts([]));
+
+        let mut dfa = Dfa::new();
+        dfa.add_state(true);
+        let dense = dfa.to_dense();
+        assert!(dense.accepts([]));
+        assert!(!dense.accepts(['a']));
+    }
+}
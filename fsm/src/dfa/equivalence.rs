@@ -0,0 +1,170 @@
+use std::collections::BTreeSet;
+
+use crate::alphabet::Alphabet;
+use crate::dfa::state::StateId;
+use crate::dfa::Dfa;
+
+impl<A: Alphabet> Dfa<A> {
+    /// Checks whether `self` and `other` recognize the same language, using
+    /// the Hopcroft–Karp union-find algorithm.
+    ///
+    /// Missing transitions are treated as going into a non-accepting sink.
+    /// If the languages differ, returns the shortest (and among those, the
+    /// lexicographically smallest) word accepted by exactly one of the DFAs.
+    pub fn equivalent(&self, other: &Dfa<A>) -> Result<(), Vec<A>> {
+        let alphabet: BTreeSet<A> = self
+            .transitions()
+            .chain(other.transitions())
+            .map(|(_, symbol, _)| symbol)
+            .collect();
+
+        // Both DFAs share one union-find universe: states of `self`, then
+        // states of `other`, then the common sink.
+        let offset = self.states.id_bound();
+        let sink = offset + other.states.id_bound();
+        let next = |element: usize, symbol: A| {
+            let next = if element < offset {
+                self.next(StateId::new(element), symbol).map(StateId::index)
+            } else if element < sink {
+                other
+                    .next(StateId::new(element - offset), symbol)
+                    .map(|s| s.index() + offset)
+            } else {
+                None
+            };
+            next.unwrap_or(sink)
+        };
+        let accepting = |element: usize| {
+            if element < offset {
+                self.accepting(StateId::new(element))
+            } else if element < sink {
+                other.accepting(StateId::new(element - offset))
+            } else {
+                false
+            }
+        };
+
+        let mut sets = UnionFind::new(sink + 1);
+        let p0 = self.initial.map_or(sink, StateId::index);
+        let q0 = other.initial.map_or(sink, |s| s.index() + offset);
+        sets.union(p0, q0);
+        let mut stack = vec![(p0, q0)];
+        while let Some((p, q)) = stack.pop() {
+            if accepting(p) != accepting(q) {
+                let word = self.symmetric_difference(other).shortest_word();
+                return Err(
+                    word.expect("languages differ, so the symmetric difference is not empty")
+                );
+            }
+            for &symbol in &alphabet {
+                let (p, q) = (next(p, symbol), next(q, symbol));
+                if sets.union(p, q) {
+                    stack.push((p, q));
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+struct UnionFind {
+    parent: Vec<usize>,
+}
+
+impl UnionFind {
+    fn new(size: usize) -> Self {
+        Self {
+            parent: (0..size).collect(),
+        }
+    }
+
+    fn find(&mut self, mut x: usize) -> usize {
+        while self.parent[x] != x {
+            self.parent[x] = self.parent[self.parent[x]];
+            x = self.parent[x];
+        }
+        x
+    }
+
+    /// Merges the sets of `x` and `y`, returning `false` if they were already merged.
+    fn union(&mut self, x: usize, y: usize) -> bool {
+        let (x, y) = (self.find(x), self.find(y));
+        if x == y {
+            return false;
+        }
+        self.parent[x] = y;
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::nfa::Nfa;
+
+    use super::*;
+
+    #[test]
+    fn test_equivalent_to_minimized() {
+        let mut nfa = Nfa::new();
+        // Words ending with "ab"
+        let s0 = nfa.add_state(false);
+        let s1 = nfa.add_state(false);
+        let s2 = nfa.add_state(true);
+        nfa.add_transition(s0, 'a', s0);
+        nfa.add_transition(s0, 'b', s0);
+        nfa.add_transition(s0, 'a', s1);
+        nfa.add_transition(s1, 'b', s2);
+
+        let dfa = nfa.to_dfa(&['a', 'b']);
+        assert_eq!(dfa.equivalent(&dfa.minimize()), Ok(()));
+        assert_eq!(dfa.minimize().equivalent(&dfa), Ok(()));
+        assert_eq!(dfa.equivalent(&dfa.complete(&['a', 'b'])), Ok(()));
+    }
+
+    #[test]
+    fn test_counterexample() {
+        let mut even = Dfa::new();
+        // Words with an even number of 'a's
+        let e0 = even.add_state(true);
+        let e1 = even.add_state(false);
+        even.add_transition(e0, 'a', e1);
+        even.add_transition(e1, 'a', e0);
+        even.add_transition(e0, 'b', e0);
+        even.add_transition(e1, 'b', e1);
+
+        let mut mod4 = Dfa::new();
+        // Words with a number of 'a's divisible by 4
+        let m0 = mod4.add_state(true);
+        let m1 = mod4.add_state(false);
+        let m2 = mod4.add_state(false);
+        let m3 = mod4.add_state(false);
+        mod4.add_transition(m0, 'a', m1);
+        mod4.add_transition(m1, 'a', m2);
+        mod4.add_transition(m2, 'a', m3);
+        mod4.add_transition(m3, 'a', m0);
+        for m in [m0, m1, m2, m3] {
+            mod4.add_transition(m, 'b', m);
+        }
+
+        assert_eq!(even.equivalent(&mod4), Err(vec!['a', 'a']));
+        assert_eq!(mod4.equivalent(&even), Err(vec!['a', 'a']));
+    }
+
+    #[test]
+    fn test_counterexample_partial() {
+        let mut x = Dfa::new();
+        let q0 = x.add_state(false);
+        let q1 = x.add_state(false);
+        let q2 = x.add_state(true);
+        x.add_transition(q0, 'a', q1);
+        x.add_transition(q1, 'b', q2);
+        x.add_transition(q1, 'c', q2);
+
+        let mut y = x.clone();
+        y.add_transition(q2, 'c', q2);
+
+        assert_eq!(x.equivalent(&y), Err(vec!['a', 'b', 'c']));
+        assert_eq!(x.equivalent(&Dfa::new()), Err(vec!['a', 'b']));
+        assert_eq!(Dfa::<char>::new().equivalent(&Dfa::new()), Ok(()));
+    }
+}
@@ -1,58 +1,34 @@
 use std::fmt::Display;
 
-use graphviz_rust::dot_generator::{attr, edge, graph, id, node, node_id, stmt};
-use graphviz_rust::dot_structures::{
-    Attribute, Edge, EdgeTy, Graph, Id, Node, NodeId, Stmt, Vertex,
-};
-use graphviz_rust::printer::{DotPrinter, PrinterContext};
+use graphviz_rust::dot_generator::{attr, id};
+use graphviz_rust::dot_structures::{Attribute, Id};
 
 use crate::alphabet::Alphabet;
 use crate::dfa::Dfa;
+use crate::util::graphviz::{render, Style};
 
 impl<A: Alphabet + Display> Dfa<A> {
     pub fn render_graphviz(&self) -> String {
-        let mut stmts = Vec::new();
-
-        stmts.push(stmt!(attr!("rankdir", "LR")));
-
-        // States:
-        for state in self.states() {
-            let name = format!("{}", state.id);
-            let attr = attr!(
-                "shape",
-                if state.accepting {
+        let style = Style {
+            name: "DFA",
+            strict: true,
+            start: vec![attr!("shape", "point")],
+        };
+        render(
+            self,
+            style,
+            |state| {
+                let shape = if self.accepting(state) {
                     "doublecircle"
                 } else {
                     "circle"
-                }
-            );
-            let node = node!( name; attr );
-            stmts.push(stmt!(node));
-        }
-
-        // Initial state:
-        if !self.states.is_empty() {
-            let attr = attr!("shape", "point");
-            let node = node!( "start"; attr );
-            stmts.push(stmt!(node));
-            let edge = edge!( node_id!("start") => node_id!(0) );
-            stmts.push(stmt!(edge));
-        }
-
-        // Transitions:
-        for (from, symbol, to) in self.transitions() {
-            let from = format!("{}", from.id);
-            let to = format!("{}", to.id);
-            let symbol = format!("{}", symbol);
-            let attr = attr!("label", symbol);
-            let edge = edge!( node_id!(from) => node_id!(to); attr );
-            stmts.push(stmt!(edge));
-        }
-
-        let g = graph!( strict di id!("DFA"), stmts );
-        let mut ctx = PrinterContext::default();
-        ctx.with_semi();
-        g.print(&mut ctx)
+                };
+                vec![attr!("shape", shape)]
+            },
+            self.transitions()
+                .map(|(from, symbol, to)| (from.id, symbol, to.id)),
+            |symbol| vec![attr!("label", symbol)],
+        )
     }
 
     // pub fn render_graphviz(&self) -> String {
@@ -90,3 +66,33 @@ impl<A: Alphabet + Display> Dfa<A> {
     //     Ok(())
     // }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_graphviz() {
+        let mut dfa = Dfa::new();
+        let a = dfa.add_state(false);
+        let b = dfa.add_state(true);
+        dfa.add_transition(a, 'a', b);
+        dfa.add_transition(a, 'b', a);
+        dfa.add_transition(b, 'b', a);
+        dfa.add_transition(b, 'a', b);
+
+        let expected = "\
+strict digraph DFA {
+    rankdir=LR;
+    0[shape=circle];
+    1[shape=doublecircle];
+    start[shape=point];
+    start -> 0 ;
+    0 -> 1 [label=a];
+    0 -> 0 [label=b];
+    1 -> 1 [label=a];
+    1 -> 0 [label=b];
+}";
+        assert_eq!(dfa.render_graphviz(), expected);
+    }
+}
@@ -0,0 +1,301 @@
+use std::collections::{BTreeSet, HashMap, VecDeque};
+
+use crate::alphabet::Alphabet;
+use crate::dfa::state::StateId;
+use crate::dfa::Dfa;
+use crate::util::dfs::dfs;
+use crate::util::partition::coarsest_partition;
+
+impl<A: Alphabet> Dfa<A> {
+    /// Returns the minimal DFA recognizing the same language, computed with
+    /// Paige and Tarjan's partition refinement, which on a DFA amounts to
+    /// Hopcroft's algorithm.
+    ///
+    /// Missing transitions are treated as going into an implicit dead state,
+    /// which is dropped again from the result, so the minimal DFA is partial
+    /// as well. States are numbered in BFS order from the initial state, which
+    /// gets id 0.
+    pub fn minimize(&self) -> Dfa<A> {
+        let Some(initial) = self.initial else {
+            return Dfa::new();
+        };
+
+        // Only states reachable from the initial state take part in the refinement.
+        let mut reachable: Vec<StateId> =
+            dfs(initial, |s| self.state(s).transitions().map(|(_, to)| to)).collect();
+        reachable.sort_unstable();
+        let index: HashMap<StateId, usize> =
+            reachable.iter().enumerate().map(|(i, &s)| (s, i)).collect();
+        let alphabet: Vec<A> = reachable
+            .iter()
+            .flat_map(|&s| self.state(s).transitions().map(|(symbol, _)| symbol))
+            .collect::<BTreeSet<_>>()
+            .into_iter()
+            .collect();
+
+        // Dense transition table over reachable states plus the dead state `n`.
+        let n = reachable.len();
+        let dead = n;
+        let mut delta = vec![vec![dead; alphabet.len()]; n + 1];
+        for (i, &s) in reachable.iter().enumerate() {
+            for (c, &symbol) in alphabet.iter().enumerate() {
+                if let Some(to) = self.next(s, symbol) {
+                    delta[i][c] = index[&to];
+                }
+            }
+        }
+        let accepting: Vec<bool> = reachable
+            .iter()
+            .map(|&s| self.accepting(s))
+            .chain(std::iter::once(false))
+            .collect();
+
+        let edges: Vec<(usize, usize, usize)> = delta
+            .iter()
+            .enumerate()
+            .flat_map(|(from, row)| row.iter().enumerate().map(move |(c, &to)| (from, c, to)))
+            .collect();
+        let block_of = coarsest_partition(&accepting, &edges);
+        let mut representative = HashMap::new();
+        for (state, &block) in block_of.iter().enumerate() {
+            representative.entry(block).or_insert(state);
+        }
+
+        // Build the quotient automaton, numbering classes in BFS order and
+        // skipping the class of the dead state.
+        let dead_block = block_of[dead];
+        let mut dfa = Dfa::new();
+        let initial_block = block_of[index[&initial]];
+        let mut block2state = HashMap::new();
+        block2state.insert(initial_block, dfa.add_state(self.accepting(initial)));
+        let mut queue = VecDeque::from([initial_block]);
+        while let Some(block) = queue.pop_front() {
+            if block == dead_block {
+                continue;
+            }
+            let from = block2state[&block];
+            for (c, &symbol) in alphabet.iter().enumerate() {
+                let to_block = block_of[delta[representative[&block]][c]];
+                if to_block == dead_block {
+                    continue;
+                }
+                let to = *block2state.entry(to_block).or_insert_with(|| {
+                    queue.push_back(to_block);
+                    dfa.add_state(accepting[representative[&to_block]])
+                });
+                dfa.add_transition(from, symbol, to);
+            }
+        }
+        dfa
+    }
+
+    /// Returns the minimal DFA recognizing the same language, computed with
+    /// Brzozowski's algorithm: `determinize(reverse(determinize(reverse(self))))`.
+    ///
+    /// The subset construction only explores reachable subsets over the
+    /// given alphabet, so the result is the same partial minimal DFA as the
+    /// one returned by [`Dfa::minimize`], up to state numbering.
+    pub fn minimize_brzozowski(&self, alphabet: &[A]) -> Dfa<A> {
+        if self.initial.is_none() {
+            return Dfa::new();
+        }
+        self.determinize_reverse(alphabet)
+            .determinize_reverse(alphabet)
+    }
+
+    /// Subset construction applied directly to the reversed transitions,
+    /// starting from the set of accepting states.
+    ///
+    /// This is `self.reverse().to_dfa(alphabet)`, without materializing the
+    /// intermediate NFA.
+    fn determinize_reverse(&self, alphabet: &[A]) -> Dfa<A> {
+        let mut predecessors: HashMap<(StateId, A), Vec<StateId>> = HashMap::new();
+        for (from, symbol, to) in self.transitions() {
+            predecessors
+                .entry((to.id, symbol))
+                .or_default()
+                .push(from.id);
+        }
+
+        let mut dfa = Dfa::new();
+        let mut state_map = HashMap::new();
+        let mut queue = Vec::new();
+
+        let initial: BTreeSet<StateId> = self
+            .states()
+            .filter(|state| state.accepting)
+            .map(|state| state.id)
+            .collect();
+        let is_initial = |set: &BTreeSet<StateId>| self.initial.is_some_and(|s| set.contains(&s));
+        state_map.insert(initial.clone(), dfa.add_state(is_initial(&initial)));
+        queue.push(initial);
+
+        while let Some(current) = queue.pop() {
+            let current_state = state_map[&current];
+            for &symbol in alphabet {
+                let next: BTreeSet<StateId> = current
+                    .iter()
+                    .filter_map(|&s| predecessors.get(&(s, symbol)))
+                    .flatten()
+                    .copied()
+                    .collect();
+                if !next.is_empty() {
+                    let next_state = *state_map.entry(next.clone()).or_insert_with(|| {
+                        let new_state = dfa.add_state(is_initial(&next));
+                        queue.push(next);
+                        new_state
+                    });
+                    dfa.add_transition(current_state, symbol, next_state);
+                }
+            }
+        }
+
+        dfa
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::nfa::Nfa;
+    use crate::test_common::generate_words;
+
+    use super::*;
+
+    #[test]
+    fn test_minimize_redundant_dfa() {
+        let mut dfa = Dfa::new();
+        // Counts zeros modulo 4, accepting an even count
+        let q0 = dfa.add_state(true);
+        let q1 = dfa.add_state(false);
+        let q2 = dfa.add_state(true);
+        let q3 = dfa.add_state(false);
+        // Unreachable state
+        let u = dfa.add_state(true);
+        dfa.add_transition(q0, '0', q1);
+        dfa.add_transition(q1, '0', q2);
+        dfa.add_transition(q2, '0', q3);
+        dfa.add_transition(q3, '0', q0);
+        for q in [q0, q1, q2, q3, u] {
+            dfa.add_transition(q, '1', q);
+        }
+
+        let min = dfa.minimize();
+        assert_eq!(min.num_states(), 2);
+        assert_eq!(min.num_transitions(), 4);
+        for word in generate_words(&['0', '1'], 10) {
+            assert_eq!(dfa.accepts(word.clone()), min.accepts(word));
+        }
+    }
+
+    #[test]
+    fn test_minimize_partial_dfa() {
+        let mut dfa = Dfa::new();
+        // Accepts "ab" and "cb" only, with a useless non-accepting branch
+        let s = dfa.add_state(false);
+        let a = dfa.add_state(false);
+        let c = dfa.add_state(false);
+        let f1 = dfa.add_state(true);
+        let f2 = dfa.add_state(true);
+        let trap = dfa.add_state(false);
+        dfa.add_transition(s, 'a', a);
+        dfa.add_transition(s, 'c', c);
+        dfa.add_transition(s, 'b', trap);
+        dfa.add_transition(trap, 'b', trap);
+        dfa.add_transition(a, 'b', f1);
+        dfa.add_transition(c, 'b', f2);
+
+        let min = dfa.minimize();
+        assert_eq!(min.num_states(), 3);
+        assert_eq!(min.num_transitions(), 3);
+        for word in generate_words(&['a', 'b', 'c'], 6) {
+            assert_eq!(dfa.accepts(word.clone()), min.accepts(word));
+        }
+    }
+
+    #[test]
+    fn test_minimize_empty_language() {
+        let mut dfa = Dfa::new();
+        let a = dfa.add_state(false);
+        let b = dfa.add_state(false);
+        dfa.add_transition(a, 'x', b);
+        dfa.add_transition(b, 'x', a);
+
+        let min = dfa.minimize();
+        assert_eq!(min.num_states(), 1);
+        assert_eq!(min.num_transitions(), 0);
+        assert!(!min.accepts("".chars()));
+        assert!(!min.accepts("xx".chars()));
+    }
+
+    #[test]
+    fn test_minimize_nfa_to_dfa() {
+        let mut nfa = Nfa::new();
+        // Words whose third-to-last symbol is 'a'
+        let s0 = nfa.add_state(false);
+        let s1 = nfa.add_state(false);
+        let s2 = nfa.add_state(false);
+        let s3 = nfa.add_state(true);
+        nfa.add_transition(s0, 'a', s0);
+        nfa.add_transition(s0, 'b', s0);
+        nfa.add_transition(s0, 'a', s1);
+        nfa.add_transition(s1, 'a', s2);
+        nfa.add_transition(s1, 'b', s2);
+        nfa.add_transition(s2, 'a', s3);
+        nfa.add_transition(s2, 'b', s3);
+
+        let dfa = nfa.to_dfa(&['a', 'b']);
+        let min = dfa.minimize();
+        assert_eq!(min.num_states(), 8);
+        assert_eq!(min.minimize().num_states(), 8);
+        for word in generate_words(&['a', 'b'], 10) {
+            assert_eq!(dfa.accepts(word.clone()), min.accepts(word));
+        }
+    }
+
+    #[test]
+    fn test_minimize_brzozowski() {
+        let mut nfa = Nfa::new();
+        // Words containing "aba" as a factor
+        let s0 = nfa.add_state(false);
+        let s1 = nfa.add_state(false);
+        let s2 = nfa.add_state(false);
+        let s3 = nfa.add_state(true);
+        nfa.add_transition(s0, 'a', s0);
+        nfa.add_transition(s0, 'b', s0);
+        nfa.add_transition(s0, 'a', s1);
+        nfa.add_transition(s1, 'b', s2);
+        nfa.add_transition(s2, 'a', s3);
+        nfa.add_transition(s3, 'a', s3);
+        nfa.add_transition(s3, 'b', s3);
+
+        let dfa = nfa.to_dfa(&['a', 'b']);
+        let hopcroft = dfa.minimize();
+        let brzozowski = dfa.minimize_brzozowski(&['a', 'b']);
+        assert_eq!(hopcroft.num_states(), 4);
+        assert_eq!(brzozowski.num_states(), hopcroft.num_states());
+        assert_eq!(brzozowski.num_transitions(), hopcroft.num_transitions());
+        for word in generate_words(&['a', 'b'], 10) {
+            assert_eq!(dfa.accepts(word.clone()), brzozowski.accepts(word));
+        }
+    }
+
+    #[test]
+    fn test_minimize_brzozowski_partial() {
+        let mut dfa = Dfa::new();
+        let s = dfa.add_state(false);
+        let a = dfa.add_state(false);
+        let c = dfa.add_state(false);
+        let f1 = dfa.add_state(true);
+        let f2 = dfa.add_state(true);
+        dfa.add_transition(s, 'a', a);
+        dfa.add_transition(s, 'c', c);
+        dfa.add_transition(a, 'b', f1);
+        dfa.add_transition(c, 'b', f2);
+
+        let min = dfa.minimize_brzozowski(&['a', 'b', 'c']);
+        assert_eq!(min.num_states(), 3);
+        for word in generate_words(&['a', 'b', 'c'], 6) {
+            assert_eq!(dfa.accepts(word.clone()), min.accepts(word));
+        }
+    }
+}
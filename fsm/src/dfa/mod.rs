@@ -1,57 +1,150 @@
-use std::ops::{Index, IndexMut};
-
+pub use decision::PumpingWitness;
+pub use dense::DenseDfa;
 use state::{State, StateId};
 
 use crate::alphabet::Alphabet;
+use crate::automaton::{Acceptor, Automaton, Build};
+use crate::error::Error;
 use crate::util::arena::Arena;
+use crate::util::machine::impl_states;
 
+mod complement;
+mod count;
+mod decision;
+mod dense;
+mod equivalence;
 pub mod graphviz;
+mod minimize;
+mod product;
+mod regex;
+mod reverse;
 pub mod state;
+mod trim;
+mod words;
 
 #[cfg(feature = "serde")]
 mod serde;
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct Dfa<A: Alphabet> {
     states: Arena<State<A>>,
+    initial: Option<StateId>,
 }
 
+impl_states!(Dfa<A>, State<A>, StateId);
+
 impl<A: Alphabet> Dfa<A> {
-    pub fn new() -> Self {
-        Self {
-            states: Arena::new(),
+    /// Adds a new state. The first state added becomes the initial one.
+    pub fn add_state(&mut self, accepting: bool) -> StateId {
+        let id = self.alloc_state(|id| State::new(id, accepting));
+        if self.states.len() == 1 {
+            self.initial = Some(id);
         }
+        id
     }
 
-    pub fn add_state(&mut self, accepting: bool) -> StateId {
-        self.states.alloc_with_id(|id| State::new(id, accepting))
+    /// Returns the initial state, or `None` if the DFA has no states.
+    pub fn initial(&self) -> Option<StateId> {
+        self.initial
     }
 
-    pub fn add_transition(&mut self, from: StateId, symbol: A, to: StateId) {
-        self.state_mut(from).add_transition(symbol, to);
+    pub fn set_initial(&mut self, state: StateId) {
+        self.try_set_initial(state)
+            .unwrap_or_else(|e| panic!("{}", e));
     }
 
-    pub fn state(&self, index: StateId) -> &State<A> {
-        &self.states[index]
+    pub fn try_set_initial(&mut self, state: StateId) -> Result<(), Error> {
+        self.check_state(state)?;
+        self.initial = Some(state);
+        Ok(())
     }
-    pub fn state_mut(&mut self, index: StateId) -> &mut State<A> {
-        &mut self.states[index]
+
+    /// Removes a state along with all transitions leading into it, and
+    /// returns it.
+    ///
+    /// The id of the removed state is handed out again by the next call to
+    /// [`Dfa::add_state`], after which any copy of it kept by the caller
+    /// silently refers to the new state.
+    ///
+    /// If the state was initial, the machine is left without an initial state;
+    /// a state added later only becomes initial if the machine was empty.
+    pub fn remove_state(&mut self, state: StateId) -> State<A> {
+        self.try_remove_state(state)
+            .unwrap_or_else(|e| panic!("{}", e))
     }
 
-    pub fn accepting(&self, state: StateId) -> bool {
-        self.state(state).accepting
+    pub fn try_remove_state(&mut self, state: StateId) -> Result<State<A>, Error> {
+        let removed = self.detach_state(state, |to| (to != state).then_some(to))?;
+        if self.initial == Some(state) {
+            self.initial = None;
+        }
+        Ok(removed)
     }
 
-    pub fn num_states(&self) -> usize {
-        self.states.len()
+    pub fn remove_transition(&mut self, from: StateId, symbol: A) -> Option<StateId> {
+        self.state_mut(from).remove_transition(symbol)
     }
 
-    pub fn num_transitions(&self) -> usize {
-        self.states().map(|state| state.num_transitions()).sum()
+    /// Merges state `b` into state `a`: transitions into `b` are redirected
+    /// to `a`, `a` becomes accepting if `b` was, and `a` inherits the
+    /// outgoing transitions of `b` on symbols it has no transition for.
+    /// If `b` was initial, `a` becomes initial.
+    /// `b` is removed as by [`Dfa::remove_state`].
+    pub fn merge_states(&mut self, a: StateId, b: StateId) {
+        self.try_merge_states(a, b)
+            .unwrap_or_else(|e| panic!("{}", e));
+    }
+
+    pub fn try_merge_states(&mut self, a: StateId, b: StateId) -> Result<(), Error> {
+        self.check_state(a)?;
+        if a == b {
+            return Ok(());
+        }
+        let redirect = |to| if to == b { a } else { to };
+        let removed = self.detach_state(b, |to| Some(redirect(to)))?;
+        if self.initial == Some(b) {
+            self.initial = Some(a);
+        }
+        let target = self.state_mut(a);
+        target.accepting |= removed.accepting;
+        for (symbol, to) in removed.transitions() {
+            if target.next(symbol).is_none() {
+                target.add_transition(symbol, redirect(to));
+            }
+        }
+        Ok(())
     }
 
-    pub fn states(&self) -> impl Iterator<Item = &State<A>> {
-        self.states.iter()
+    /// Renumbers the states to close the gaps left by removed states,
+    /// preserving their relative order.
+    pub fn compact(&mut self) {
+        let old2new = self.compact_states();
+        self.initial = self.initial.map(|s| old2new[&s]);
+    }
+
+    pub fn add_transition(&mut self, from: StateId, symbol: A, to: StateId) {
+        self.try_add_transition(from, symbol, to)
+            .unwrap_or_else(|e| panic!("{}", e));
+    }
+
+    pub fn try_add_transition(
+        &mut self,
+        from: StateId,
+        symbol: A,
+        to: StateId,
+    ) -> Result<(), Error> {
+        self.check_state(from)?;
+        self.check_state(to)?;
+        self.state_mut(from).add_transition(symbol, to);
+        Ok(())
+    }
+
+    pub fn accepting(&self, state: StateId) -> bool {
+        self.state(state).accepting
+    }
+
+    pub fn num_transitions(&self) -> usize {
+        self.states().map(|state| state.num_transitions()).sum()
     }
 
     pub fn transitions(&self) -> impl Iterator<Item = (&State<A>, A, &State<A>)> + '_ {
@@ -63,23 +156,53 @@ impl<A: Alphabet> Dfa<A> {
     }
 }
 
-impl<A: Alphabet> Default for Dfa<A> {
-    fn default() -> Self {
-        Self::new()
+impl<A: Alphabet> Automaton for Dfa<A> {
+    type StateId = StateId;
+    type Input = A;
+
+    fn num_states(&self) -> usize {
+        self.num_states()
+    }
+
+    fn state_ids(&self) -> impl Iterator<Item = StateId> + '_ {
+        self.states().map(|state| state.id)
+    }
+
+    fn initial_state_ids(&self) -> impl Iterator<Item = StateId> + '_ {
+        self.initial.into_iter()
+    }
+
+    fn successors(&self, state: StateId) -> impl Iterator<Item = (Option<A>, StateId)> + '_ {
+        self.state(state)
+            .transitions()
+            .map(|(symbol, to)| (Some(symbol), to))
     }
 }
 
-impl<A: Alphabet> Index<StateId> for Dfa<A> {
-    type Output = State<A>;
+impl<A: Alphabet> Acceptor for Dfa<A> {
+    fn is_accepting(&self, state: StateId) -> bool {
+        self.accepting(state)
+    }
 
-    fn index(&self, index: StateId) -> &Self::Output {
-        self.state(index)
+    fn accepts(&self, word: impl IntoIterator<Item = A>) -> bool {
+        self.accepts(word)
     }
 }
 
-impl<A: Alphabet> IndexMut<StateId> for Dfa<A> {
-    fn index_mut(&mut self, index: StateId) -> &mut Self::Output {
-        self.state_mut(index)
+impl<A: Alphabet> Build for Dfa<A> {
+    fn add_state_like(&mut self, source: &Self, state: StateId) -> StateId {
+        self.add_state(source.accepting(state))
+    }
+
+    fn add_successor(&mut self, from: StateId, input: Option<A>, to: StateId) {
+        // The successors of a DFA state are never ε-transitions.
+        if let Some(symbol) = input {
+            self.add_transition(from, symbol, to);
+        }
+    }
+
+    fn set_initial_state_ids(&mut self, states: impl IntoIterator<Item = StateId>) {
+        self.initial = states.into_iter().next();
     }
 }
 
@@ -89,10 +212,9 @@ impl<A: Alphabet> Dfa<A> {
     }
 
     pub fn accepts(&self, word: impl IntoIterator<Item = A>) -> bool {
-        if self.states.is_empty() {
+        let Some(mut current_state) = self.initial else {
             return false;
-        }
-        let mut current_state = 0;
+        };
         for symbol in word {
             if let Some(next_state) = self.next(current_state, symbol) {
                 current_state = next_state;
@@ -106,6 +228,8 @@ impl<A: Alphabet> Dfa<A> {
 
 #[cfg(test)]
 mod tests {
+    use crate::test_common::generate_words;
+
     use super::*;
 
     #[test]
@@ -142,4 +266,119 @@ mod tests {
         assert!(!dfa.accepts(vec![One, One, Zero, Zero, Zero]));
         assert!(!dfa.accepts(vec![One, One, Zero, Zero, One, Zero]));
     }
+
+    #[test]
+    fn test_initial_state() {
+        let mut dfa = Dfa::new();
+        // Words "ab"*, starting from `q2`
+        let q0 = dfa.add_state(false);
+        let q1 = dfa.add_state(false);
+        let q2 = dfa.add_state(true);
+        dfa.add_transition(q0, 'b', q2);
+        dfa.add_transition(q1, 'a', q0);
+        dfa.add_transition(q2, 'a', q0);
+        assert_eq!(dfa.initial(), Some(q0));
+        assert!(dfa.accepts("b".chars()));
+        dfa.set_initial(q2);
+        assert_eq!(dfa.initial(), Some(q2));
+
+        let minimized = dfa.minimize();
+        let trimmed = dfa.trim();
+        assert_eq!(minimized.num_states(), 2);
+        assert_eq!(trimmed.num_states(), 2);
+        assert_eq!(dfa.shortest_word(), Some(vec![]));
+        assert_eq!(dfa.count_words(4), Some(1));
+        for word in generate_words(&['a', 'b'], 6) {
+            let expected = word.chunks(2).all(|chunk| chunk == ['a', 'b']);
+            assert_eq!(dfa.accepts(word.clone()), expected);
+            assert_eq!(minimized.accepts(word.clone()), expected);
+            assert_eq!(trimmed.accepts(word.clone()), expected);
+            assert_eq!(dfa.reverse().accepts(word.iter().rev().copied()), expected);
+        }
+    }
+
+    #[test]
+    fn test_try_builders() {
+        let mut dfa = Dfa::new();
+        let a = dfa.add_state(false);
+        assert_eq!(
+            dfa.try_add_transition(a, 'x', StateId::new(1)),
+            Err(Error::UnknownState(1))
+        );
+        assert_eq!(
+            dfa.try_add_transition(StateId::new(2), 'x', a),
+            Err(Error::UnknownState(2))
+        );
+        assert_eq!(dfa.num_transitions(), 0);
+        assert_eq!(
+            dfa.try_merge_states(a, StateId::new(3)),
+            Err(Error::UnknownState(3))
+        );
+        assert_eq!(dfa.try_add_transition(a, 'x', a), Ok(()));
+        assert_eq!(dfa.next(a, 'x'), Some(a));
+        assert_eq!(dfa.try_state(a).map(|state| state.id), Ok(a));
+        assert_eq!(
+            dfa.try_state_mut(StateId::new(1)).map(|state| state.id),
+            Err(Error::UnknownState(1))
+        );
+    }
+
+    #[test]
+    fn test_remove_and_merge_states() {
+        let mut dfa = Dfa::new();
+        // Words over {a, b} with an even number of 'a's, with `q2`
+        // duplicating `q0` and a useless state `r`
+        let q0 = dfa.add_state(true);
+        let q1 = dfa.add_state(false);
+        let q2 = dfa.add_state(true);
+        let r = dfa.add_state(false);
+        dfa.add_transition(q0, 'a', q1);
+        dfa.add_transition(q0, 'b', q0);
+        dfa.add_transition(q1, 'a', q2);
+        dfa.add_transition(q1, 'b', q1);
+        dfa.add_transition(q2, 'a', q1);
+        dfa.add_transition(q2, 'b', q2);
+        dfa.add_transition(q2, 'c', r);
+        let original = dfa.clone();
+
+        let removed = dfa.remove_state(r);
+        assert_eq!(removed.id, r);
+        assert_eq!(dfa.num_states(), 3);
+        assert_eq!(dfa.next(q2, 'c'), None);
+        assert_eq!(dfa.remove_transition(q2, 'c'), None);
+
+        dfa.merge_states(q0, q2);
+        assert_eq!(dfa.num_states(), 2);
+        assert_eq!(dfa.next(q1, 'a'), Some(q0));
+        assert_eq!(dfa.equivalent(&original), Ok(()));
+        assert_eq!(dfa.minimize().num_states(), 2);
+
+        // Removed ids are reused
+        let s = dfa.add_state(false);
+        assert!(s == q2 || s == r);
+        dfa.remove_state(s);
+
+        dfa.remove_state(q0);
+        assert_eq!(dfa.initial(), None);
+        assert!(!dfa.accepts([]));
+        // Only a state added to an empty machine becomes initial
+        let t = dfa.add_state(true);
+        assert_eq!(dfa.initial(), None);
+        dfa.remove_state(t);
+        dfa.set_initial(q1);
+        dfa.compact();
+        let q1 = dfa.initial().unwrap();
+        assert_eq!(q1.index(), 0);
+        assert_eq!(dfa.next(q1, 'b'), Some(q1));
+    }
+
+    #[test]
+    fn test_add_state_to_empty() {
+        let mut dfa = Dfa::<char>::new();
+        let q0 = dfa.add_state(false);
+        dfa.remove_state(q0);
+        let q1 = dfa.add_state(true);
+        assert_eq!(dfa.initial(), Some(q1));
+        assert!(dfa.accepts([]));
+    }
 }
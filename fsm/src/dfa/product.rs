@@ -0,0 +1,202 @@
+use std::collections::{BTreeSet, HashMap, VecDeque};
+
+use crate::alphabet::Alphabet;
+use crate::dfa::state::StateId;
+use crate::dfa::Dfa;
+
+impl<A: Alphabet> Dfa<A> {
+    /// Product construction of two DFAs.
+    ///
+    /// A pair of states is accepting iff `op(accepting_in_self, accepting_in_other)`.
+    /// Only pairs reachable from the pair of initial states are explored, over
+    /// the union of symbols used by both automata. A missing transition leads
+    /// into an implicit non-accepting sink, represented by `None` in the pair.
+    ///
+    /// If `op(false, false)` holds, the pair of two sinks is materialized, so
+    /// the result also accepts the words over those symbols that fall off both
+    /// automata. Words with other symbols are rejected; use
+    /// [`Dfa::product_over`] to take them into account.
+    pub fn product(&self, other: &Dfa<A>, op: impl Fn(bool, bool) -> bool) -> Dfa<A> {
+        self.product_over(other, &[], op)
+    }
+
+    /// Product construction of two DFAs over `alphabet`, see [`Dfa::product`].
+    ///
+    /// The symbols used by either automaton are explored as well. The pair of
+    /// two sinks is only materialized if `op(false, false)` holds, in which
+    /// case it accepts every continuation over those symbols.
+    pub fn product_over(
+        &self,
+        other: &Dfa<A>,
+        alphabet: &[A],
+        op: impl Fn(bool, bool) -> bool,
+    ) -> Dfa<A> {
+        let alphabet: BTreeSet<A> = self
+            .transitions()
+            .chain(other.transitions())
+            .map(|(_, symbol, _)| symbol)
+            .chain(alphabet.iter().copied())
+            .collect();
+        let accepting = |(p, q): (Option<StateId>, Option<StateId>)| {
+            op(
+                p.is_some_and(|p| self.accepting(p)),
+                q.is_some_and(|q| other.accepting(q)),
+            )
+        };
+        let keep_sink = op(false, false);
+
+        let mut dfa = Dfa::new();
+        let mut state_map = HashMap::new();
+        let mut queue = VecDeque::new();
+
+        let initial = (self.initial, other.initial);
+        state_map.insert(initial, dfa.add_state(accepting(initial)));
+        queue.push_back(initial);
+
+        while let Some(current @ (p, q)) = queue.pop_front() {
+            let current_state = state_map[&current];
+            for &symbol in &alphabet {
+                let next = (
+                    p.and_then(|p| self.next(p, symbol)),
+                    q.and_then(|q| other.next(q, symbol)),
+                );
+                if next == (None, None) && !keep_sink {
+                    continue;
+                }
+                let next_state = *state_map.entry(next).or_insert_with(|| {
+                    queue.push_back(next);
+                    dfa.add_state(accepting(next))
+                });
+                dfa.add_transition(current_state, symbol, next_state);
+            }
+        }
+
+        dfa
+    }
+
+    /// Returns a DFA recognizing words accepted by both `self` and `other`.
+    pub fn intersect(&self, other: &Dfa<A>) -> Dfa<A> {
+        self.product(other, |a, b| a && b)
+    }
+
+    /// Returns a DFA recognizing words accepted by `self` or `other`.
+    pub fn union(&self, other: &Dfa<A>) -> Dfa<A> {
+        self.product(other, |a, b| a || b)
+    }
+
+    /// Returns a DFA recognizing words accepted by `self` but not by `other`.
+    pub fn difference(&self, other: &Dfa<A>) -> Dfa<A> {
+        self.product(other, |a, b| a && !b)
+    }
+
+    /// Returns a DFA recognizing words accepted by exactly one of `self` and `other`.
+    pub fn symmetric_difference(&self, other: &Dfa<A>) -> Dfa<A> {
+        self.product(other, |a, b| a != b)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::test_common::generate_words;
+
+    use super::*;
+
+    /// Words with an even number of 'a's.
+    fn even_a() -> Dfa<char> {
+        let mut dfa = Dfa::new();
+        let even = dfa.add_state(true);
+        let odd = dfa.add_state(false);
+        dfa.add_transition(even, 'a', odd);
+        dfa.add_transition(odd, 'a', even);
+        dfa.add_transition(even, 'b', even);
+        dfa.add_transition(odd, 'b', odd);
+        dfa
+    }
+
+    /// Words starting with 'b', over a partial transition function.
+    fn starts_with_b() -> Dfa<char> {
+        let mut dfa = Dfa::new();
+        let q0 = dfa.add_state(false);
+        let q1 = dfa.add_state(true);
+        dfa.add_transition(q0, 'b', q1);
+        dfa.add_transition(q1, 'a', q1);
+        dfa.add_transition(q1, 'b', q1);
+        dfa
+    }
+
+    #[test]
+    fn test_boolean_operations() {
+        let x = even_a();
+        let y = starts_with_b();
+        let intersection = x.intersect(&y);
+        let union = x.union(&y);
+        let difference = x.difference(&y);
+        let symmetric_difference = x.symmetric_difference(&y);
+
+        for word in generate_words(&['a', 'b'], 8) {
+            let a = x.accepts(word.clone());
+            let b = y.accepts(word.clone());
+            assert_eq!(intersection.accepts(word.clone()), a && b);
+            assert_eq!(union.accepts(word.clone()), a || b);
+            assert_eq!(difference.accepts(word.clone()), a && !b);
+            assert_eq!(symmetric_difference.accepts(word), a != b);
+        }
+    }
+
+    #[test]
+    fn test_product_chaining() {
+        let x = even_a();
+        let y = starts_with_b();
+        let z = x.symmetric_difference(&y).union(&x.intersect(&y));
+
+        for word in generate_words(&['a', 'b'], 8) {
+            assert_eq!(z.accepts(word.clone()), x.union(&y).accepts(word));
+        }
+    }
+
+    #[test]
+    fn test_product_sink() {
+        let x = starts_with_b();
+        let y = starts_with_b();
+
+        // Intersection does not materialize the pair of sinks...
+        let intersection = x.intersect(&y);
+        assert_eq!(intersection.num_states(), 2);
+
+        // ...but NOR accepts everything rejected by both, including words
+        // that fall off both partial automata or use other symbols.
+        let nor = x.product_over(&y, &['a', 'b', 'c'], |a, b| !a && !b);
+        assert_eq!(nor.num_states(), 3);
+        for word in generate_words(&['a', 'b', 'c'], 6) {
+            assert_eq!(nor.accepts(word.clone()), !x.accepts(word));
+        }
+    }
+
+    #[test]
+    fn test_product_xnor() {
+        let x = even_a();
+        let y = starts_with_b();
+        let xnor = x.product(&y, |a, b| a == b);
+
+        for word in generate_words(&['a', 'b'], 8) {
+            let expected = x.accepts(word.clone()) == y.accepts(word.clone());
+            assert_eq!(xnor.accepts(word), expected);
+        }
+        // Symbols neither DFA uses are only known to product_over
+        assert!(!xnor.accepts(['c']));
+        assert!(x.product_over(&y, &['c'], |a, b| a == b).accepts(['c']));
+    }
+
+    #[test]
+    fn test_product_with_empty() {
+        let x = even_a();
+        let empty = Dfa::new();
+        let union = x.union(&empty);
+        let intersection = x.intersect(&empty);
+
+        for word in generate_words(&['a', 'b'], 6) {
+            assert_eq!(union.accepts(word.clone()), x.accepts(word.clone()));
+            assert!(!intersection.accepts(word));
+        }
+    }
+}
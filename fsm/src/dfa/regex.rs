@@ -0,0 +1,121 @@
+use std::collections::HashMap;
+use std::fmt::Display;
+
+use crate::alphabet::Alphabet;
+use crate::dfa::Dfa;
+use crate::util::regex::state_elimination;
+
+impl<A: Alphabet + Display> Dfa<A> {
+    /// Returns a regular expression describing the language of the DFA, or
+    /// `None` if the language is empty.
+    ///
+    /// The expression is built by state elimination and written in the
+    /// syntax of `regex_thompson::Regex::new`: concatenation, `|`, `*`, `+`,
+    /// `?` and parentheses, with the empty word written as the empty pattern.
+    /// Symbols are written with their `Display` implementation, so they
+    /// should render as single characters; metacharacters among them are
+    /// escaped with a backslash.
+    pub fn to_regex(&self) -> Option<String> {
+        let initial = self.initial?;
+        let index: HashMap<_, _> = self.states().enumerate().map(|(i, s)| (s.id, i)).collect();
+        let accepting: Vec<bool> = self.states().map(|state| state.accepting).collect();
+        let edges = self
+            .transitions()
+            .map(|(from, symbol, to)| (index[&from.id], Some(symbol), index[&to.id]));
+        state_elimination(
+            self.num_states(),
+            [index[&initial]],
+            |s| accepting[s],
+            edges,
+        )
+        .map(|regex| regex.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use regex_thompson::Regex;
+
+    use crate::test_common::generate_strings;
+
+    use super::*;
+
+    #[test]
+    fn test_to_regex() {
+        let mut dfa = Dfa::new();
+        // Words with an even number of zeros
+        let a = dfa.add_state(true);
+        let b = dfa.add_state(false);
+        dfa.add_transition(a, '1', a);
+        dfa.add_transition(b, '1', b);
+        dfa.add_transition(a, '0', b);
+        dfa.add_transition(b, '0', a);
+
+        let pattern = dfa.to_regex().unwrap();
+        let re = Regex::new(&pattern);
+        for word in generate_strings(&['0', '1'], 8) {
+            assert_eq!(dfa.accepts(word.chars()), re.matches(&word), "{}", pattern);
+        }
+    }
+
+    #[test]
+    fn test_to_regex_simple() {
+        let mut dfa = Dfa::new();
+        let q0 = dfa.add_state(false);
+        let q1 = dfa.add_state(true);
+        let q2 = dfa.add_state(true);
+        dfa.add_transition(q0, 'a', q1);
+        dfa.add_transition(q1, 'b', q1);
+        dfa.add_transition(q1, 'c', q2);
+        assert_eq!(dfa.to_regex().unwrap(), "ab*c?");
+
+        let mut dfa = Dfa::<char>::new();
+        let q0 = dfa.add_state(true);
+        dfa.add_state(true);
+        assert_eq!(dfa.to_regex().unwrap(), "");
+        dfa.state_mut(q0).accepting = false;
+        assert_eq!(dfa.to_regex(), None);
+        assert_eq!(Dfa::<char>::new().to_regex(), None);
+    }
+
+    #[test]
+    fn test_to_regex_metacharacters() {
+        let mut dfa = Dfa::new();
+        let q0 = dfa.add_state(false);
+        let q1 = dfa.add_state(true);
+        dfa.add_transition(q0, '(', q1);
+        dfa.add_transition(q1, '*', q1);
+        dfa.add_transition(q1, '.', q0);
+        dfa.add_transition(q1, '\\', q0);
+
+        let pattern = dfa.to_regex().unwrap();
+        let re = Regex::new(&pattern);
+        for word in generate_strings(&['(', '*', '.', '\\', 'a'], 5) {
+            assert_eq!(dfa.accepts(word.chars()), re.matches(&word), "{}", pattern);
+        }
+    }
+
+    #[test]
+    fn test_to_regex_minimized() {
+        let mut dfa = Dfa::new();
+        // Words containing "aba"
+        let q0 = dfa.add_state(false);
+        let q1 = dfa.add_state(false);
+        let q2 = dfa.add_state(false);
+        let q3 = dfa.add_state(true);
+        dfa.add_transition(q0, 'a', q1);
+        dfa.add_transition(q0, 'b', q0);
+        dfa.add_transition(q1, 'a', q1);
+        dfa.add_transition(q1, 'b', q2);
+        dfa.add_transition(q2, 'a', q3);
+        dfa.add_transition(q2, 'b', q0);
+        dfa.add_transition(q3, 'a', q3);
+        dfa.add_transition(q3, 'b', q3);
+
+        let pattern = dfa.to_regex().unwrap();
+        let re = Regex::new(&pattern);
+        for word in generate_strings(&['a', 'b'], 8) {
+            assert_eq!(dfa.accepts(word.chars()), re.matches(&word), "{}", pattern);
+        }
+    }
+}
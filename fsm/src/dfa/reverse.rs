@@ -0,0 +1,76 @@
+use std::collections::HashMap;
+
+use crate::alphabet::Alphabet;
+use crate::dfa::state::StateId;
+use crate::dfa::Dfa;
+use crate::nfa::{self, Nfa};
+
+impl<A: Alphabet> Dfa<A> {
+    /// Returns an NFA recognizing the reversed language.
+    ///
+    /// Every transition is flipped, the initial state becomes the only
+    /// accepting state, and the accepting states become the initial ones.
+    /// States are renumbered in increasing order of their ids, so ids are
+    /// preserved unless states have been removed.
+    pub fn reverse(&self) -> Nfa<A> {
+        let mut nfa = Nfa::new();
+        let old2new: HashMap<StateId, nfa::state::StateId> = self
+            .states()
+            .map(|state| (state.id, nfa.add_state(Some(state.id) == self.initial)))
+            .collect();
+        nfa.set_initial_states(
+            self.states()
+                .filter(|state| state.accepting)
+                .map(|state| old2new[&state.id]),
+        );
+        for (from, symbol, to) in self.transitions() {
+            nfa.add_transition(old2new[&to.id], symbol, old2new[&from.id]);
+        }
+        nfa
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::test_common::generate_words;
+
+    use super::*;
+
+    #[test]
+    fn test_reverse() {
+        let mut dfa = Dfa::new();
+        // Words starting with "ab"
+        let q0 = dfa.add_state(false);
+        let q1 = dfa.add_state(false);
+        let q2 = dfa.add_state(true);
+        dfa.add_transition(q0, 'a', q1);
+        dfa.add_transition(q1, 'b', q2);
+        dfa.add_transition(q2, 'a', q2);
+        dfa.add_transition(q2, 'b', q2);
+
+        let nfa = dfa.reverse();
+        assert_eq!(nfa.num_states(), 3);
+        assert_eq!(
+            nfa.initial_states()
+                .iter()
+                .map(|s| s.index())
+                .collect::<Vec<_>>(),
+            vec![q2.index()]
+        );
+        assert!(nfa.accepts("ba".chars()));
+        assert!(nfa.accepts("abba".chars()));
+        assert!(!nfa.accepts("ab".chars()));
+        for word in generate_words(&['a', 'b'], 8) {
+            let reversed = word.iter().rev().copied().collect::<Vec<_>>();
+            assert_eq!(dfa.accepts(word), nfa.accepts(reversed));
+        }
+    }
+
+    #[test]
+    fn test_reverse_empty() {
+        let dfa: Dfa<char> = Dfa::new();
+        let nfa = dfa.reverse();
+        assert_eq!(nfa.num_states(), 0);
+        assert!(!nfa.accepts("".chars()));
+    }
+}
@@ -1,21 +1,23 @@
-use std::collections::HashMap;
-
+use serde::de::Error as _;
 use serde::{Deserialize, Deserializer, Serialize, Serializer};
 
 use crate::alphabet::Alphabet;
 use crate::dfa::Dfa;
+use crate::util::serde::{present, Renumbering};
 
-use super::State;
+use super::{State, StateId};
 
 impl<A: Alphabet + Serialize> Serialize for Dfa<A> {
     fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
         #[derive(Serialize)]
         #[serde(rename = "Dfa")]
         struct DfaHelper<'a, A: Alphabet> {
+            initial: Option<StateId>,
             states: Vec<&'a State<A>>,
         }
 
         let helper = DfaHelper {
+            initial: self.initial,
             states: self.states().collect(),
         };
         helper.serialize(serializer)
@@ -30,22 +32,34 @@ impl<'de, A: Alphabet + Deserialize<'de>> Deserialize<'de> for Dfa<A> {
         #[derive(Deserialize)]
         #[serde(rename = "Dfa")]
         struct DfaHelper<A: Alphabet> {
+            /// Defaults to the first listed state when absent; `null` means
+            /// no initial state.
+            #[serde(default, deserialize_with = "present")]
+            initial: Option<Option<StateId>>,
             states: Vec<State<A>>,
         }
 
         let helper = DfaHelper::deserialize(deserializer)?;
         let mut dfa = Dfa::new();
-        let old2new: HashMap<_, _> = helper
-            .states
-            .iter()
-            .map(|state| (state.id, dfa.add_state(state.accepting)))
-            .collect();
+        let ids = Renumbering::new(
+            helper
+                .states
+                .iter()
+                .map(|state| (state.id, dfa.add_state(state.accepting))),
+        )
+        .map_err(D::Error::custom)?;
+        let new_id = |old: StateId| ids.get(old).map_err(D::Error::custom);
         for old_from_state in &helper.states {
-            let new_from = old2new[&old_from_state.id];
+            let new_from = new_id(old_from_state.id)?;
             for (symbol, old_to) in old_from_state.transitions() {
-                dfa.add_transition(new_from, symbol, old2new[&old_to]);
+                dfa.add_transition(new_from, symbol, new_id(old_to)?);
             }
         }
+        match helper.initial {
+            Some(Some(old_initial)) => dfa.set_initial(new_id(old_initial)?),
+            Some(None) => dfa.initial = None,
+            None => {}
+        }
         Ok(dfa)
     }
 }
@@ -73,4 +87,69 @@ mod tests {
             assert_eq!(dfa.accepts(word.chars()), dfa2.accepts(word.chars()));
         }
     }
+
+    #[test]
+    fn test_dfa_serde_initial() {
+        let mut dfa = Dfa::new();
+        let a = dfa.add_state(false);
+        let b = dfa.add_state(true);
+        dfa.add_transition(a, 'x', b);
+        dfa.add_transition(b, 'x', a);
+        dfa.set_initial(b);
+
+        let json = serde_json::to_string(&dfa).unwrap();
+        let dfa2 = decltype(&dfa, serde_json::from_str(&json).unwrap());
+        assert_eq!(dfa2.initial(), Some(b));
+        assert!(dfa2.accepts("".chars()));
+        assert!(!dfa2.accepts("x".chars()));
+
+        // Without an explicit initial state, the first listed state is initial
+        let json = r#"{"states":[
+            {"id":7,"accepting":true,"transitions":{"x":3}},
+            {"id":3,"accepting":false,"transitions":{}}
+        ]}"#;
+        let dfa3: Dfa<char> = serde_json::from_str(json).unwrap();
+        assert_eq!(dfa3.initial(), Some(StateId::new(0)));
+        assert!(dfa3.accepts("".chars()));
+    }
+
+    #[test]
+    fn test_dfa_serde_removed_initial() {
+        let mut dfa = Dfa::new();
+        let a = dfa.add_state(true);
+        let b = dfa.add_state(false);
+        dfa.add_transition(b, 'x', b);
+        dfa.remove_state(a);
+
+        let json = serde_json::to_string(&dfa).unwrap();
+        assert!(json.contains(r#""initial":null"#), "{}", json);
+        let dfa2 = decltype(&dfa, serde_json::from_str(&json).unwrap());
+        assert_eq!(dfa2.initial(), None);
+        assert_eq!(dfa2.num_states(), 1);
+    }
+
+    #[test]
+    fn test_dfa_serde_errors() {
+        let dangling = r#"{"states":[{"id":0,"accepting":true,"transitions":{"x":5}}]}"#;
+        let error = serde_json::from_str::<Dfa<char>>(dangling).unwrap_err();
+        assert!(
+            error.to_string().contains("unknown state id 5"),
+            "{}",
+            error
+        );
+
+        let duplicate = r#"{"states":[
+            {"id":0,"accepting":true,"transitions":{}},
+            {"id":0,"accepting":false,"transitions":{}}
+        ]}"#;
+        let error = serde_json::from_str::<Dfa<char>>(duplicate).unwrap_err();
+        assert!(
+            error.to_string().contains("duplicate state id 0"),
+            "{}",
+            error
+        );
+
+        let bad_initial = r#"{"initial":1,"states":[{"id":0,"accepting":true,"transitions":{}}]}"#;
+        assert!(serde_json::from_str::<Dfa<char>>(bad_initial).is_err());
+    }
 }
@@ -1,15 +1,16 @@
-use std::collections::HashMap;
+use std::collections::BTreeMap;
 
 use crate::alphabet::Alphabet;
+use crate::util::id::state_id;
 
-pub type StateId = usize;
+state_id!("DFA");
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct State<A: Alphabet> {
     pub id: StateId,
     pub accepting: bool,
-    transitions: HashMap<A, StateId>,
+    transitions: BTreeMap<A, StateId>,
 }
 
 impl<A: Alphabet> State<A> {
@@ -17,7 +18,7 @@ impl<A: Alphabet> State<A> {
         Self {
             id,
             accepting,
-            transitions: HashMap::new(),
+            transitions: BTreeMap::new(),
         }
     }
 
@@ -25,6 +26,18 @@ impl<A: Alphabet> State<A> {
         self.transitions.insert(symbol, to);
     }
 
+    pub fn remove_transition(&mut self, symbol: A) -> Option<StateId> {
+        self.transitions.remove(&symbol)
+    }
+
+    /// Replaces every target `to` by `f(to)`, dropping transitions for which it is `None`.
+    pub(crate) fn redirect(&mut self, mut f: impl FnMut(StateId) -> Option<StateId>) {
+        self.transitions = std::mem::take(&mut self.transitions)
+            .into_iter()
+            .filter_map(|(symbol, to)| Some((symbol, f(to)?)))
+            .collect();
+    }
+
     pub fn num_transitions(&self) -> usize {
         self.transitions.len()
     }
@@ -0,0 +1,80 @@
+use std::collections::BTreeSet;
+
+use crate::alphabet::Alphabet;
+use crate::automaton::{self, Acceptor, Automaton};
+use crate::dfa::state::StateId;
+use crate::dfa::Dfa;
+
+impl<A: Alphabet> Dfa<A> {
+    /// Returns the states reachable from the initial state.
+    pub fn reachable_states(&self) -> BTreeSet<StateId> {
+        Automaton::reachable_states(self)
+    }
+
+    /// Returns the states from which some accepting state is reachable.
+    pub fn coreachable_states(&self) -> BTreeSet<StateId> {
+        Acceptor::coreachable_states(self)
+    }
+
+    /// Returns an equivalent DFA containing only the states that are both
+    /// reachable and co-reachable, renumbered in increasing order of their
+    /// old ids.
+    ///
+    /// The initial state is always kept, so the result of trimming a DFA
+    /// with an empty language is a single non-accepting state.
+    pub fn trim(&self) -> Dfa<A> {
+        automaton::trim(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::test_common::generate_words;
+
+    use super::*;
+
+    #[test]
+    fn test_trim() {
+        let mut dfa = Dfa::new();
+        let a = dfa.add_state(false);
+        let b = dfa.add_state(true);
+        // Unreachable state
+        let s = dfa.add_state(true);
+        // Reachable, but not co-reachable
+        let trap = dfa.add_state(false);
+        dfa.add_transition(a, 'a', b);
+        dfa.add_transition(a, 'b', a);
+        dfa.add_transition(b, 'b', a);
+        dfa.add_transition(b, 'a', b);
+        dfa.add_transition(b, 'c', trap);
+        dfa.add_transition(trap, 'a', trap);
+        dfa.add_transition(s, 'a', b);
+
+        assert_eq!(dfa.reachable_states(), BTreeSet::from([a, b, trap]));
+        assert_eq!(dfa.coreachable_states(), BTreeSet::from([a, b, s]));
+
+        let trimmed = dfa.trim();
+        assert_eq!(trimmed.num_states(), 2);
+        assert_eq!(trimmed.num_transitions(), 4);
+        for word in generate_words(&['a', 'b', 'c'], 6) {
+            assert_eq!(dfa.accepts(word.clone()), trimmed.accepts(word));
+        }
+    }
+
+    #[test]
+    fn test_trim_empty_language() {
+        let mut dfa = Dfa::new();
+        let a = dfa.add_state(false);
+        let b = dfa.add_state(false);
+        let c = dfa.add_state(true);
+        dfa.add_transition(a, 'x', b);
+        dfa.add_transition(c, 'x', a);
+
+        let trimmed = dfa.trim();
+        assert_eq!(trimmed.num_states(), 1);
+        assert_eq!(trimmed.num_transitions(), 0);
+        assert!(!trimmed.accepting(StateId::new(0)));
+
+        assert_eq!(Dfa::<char>::new().trim().num_states(), 0);
+    }
+}
@@ -0,0 +1,167 @@
+use std::collections::{BTreeSet, HashSet};
+
+use crate::alphabet::Alphabet;
+use crate::dfa::state::StateId;
+use crate::dfa::Dfa;
+
+impl<A: Alphabet> Dfa<A> {
+    /// Returns the shortest accepted word (the lexicographically smallest
+    /// among those of minimal length), or `None` if the language is empty.
+    pub fn shortest_word(&self) -> Option<Vec<A>> {
+        self.shortest_path(self.initial?, |s| self.accepting(s))
+    }
+
+    /// Enumerates all accepted words of length at most `max_len` in shortlex
+    /// order (by length first, then lexicographically).
+    ///
+    /// Computes, one length at a time, the states from which an accepting
+    /// state is reachable in exactly `k` steps, so that the enumeration only
+    /// follows transitions that lead to an accepted word. When the language
+    /// is finite, enumeration stops after its longest word, so `max_len` may
+    /// be arbitrarily large.
+    pub fn words(&self, max_len: usize) -> impl Iterator<Item = Vec<A>> + '_ {
+        // An accepted word of a finite language visits no state twice.
+        let max_len = if self.is_finite() {
+            max_len.min(self.num_states())
+        } else {
+            max_len
+        };
+
+        let mut alive: Vec<HashSet<StateId>> = vec![self
+            .states()
+            .filter(|state| state.accepting)
+            .map(|state| state.id)
+            .collect()];
+
+        let sorted_transitions = |s: StateId| -> Vec<(A, StateId)> {
+            self.state(s)
+                .transitions()
+                .collect::<BTreeSet<_>>()
+                .into_iter()
+                .collect()
+        };
+
+        // DFS frames are `(state, sorted transitions, next transition index)`;
+        // `word` holds the symbols leading to the top frame.
+        let mut length = 0;
+        let mut stack = Vec::new();
+        let mut word = Vec::new();
+        std::iter::from_fn(move || loop {
+            if stack.is_empty() {
+                let initial = self.initial.filter(|_| length <= max_len)?;
+                if length == alive.len() {
+                    let next: HashSet<StateId> = self
+                        .transitions()
+                        .filter(|(_, _, to)| alive[length - 1].contains(&to.id))
+                        .map(|(from, _, _)| from.id)
+                        .collect();
+                    alive.push(next);
+                }
+                if alive[length].contains(&initial) {
+                    stack.push((initial, sorted_transitions(initial), 0));
+                }
+                length += 1;
+                continue;
+            }
+
+            let remaining = length - stack.len();
+            let (_, transitions, next) = stack.last_mut().unwrap();
+            if remaining == 0 {
+                let result = word.clone();
+                stack.pop();
+                word.pop();
+                return Some(result);
+            }
+            if let Some(&(symbol, to)) = transitions.get(*next) {
+                *next += 1;
+                if alive[remaining - 1].contains(&to) {
+                    word.push(symbol);
+                    stack.push((to, sorted_transitions(to), 0));
+                }
+            } else {
+                stack.pop();
+                word.pop();
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::test_common::generate_words;
+
+    use super::*;
+
+    /// Words over {a, b} with an odd number of 'a's, over a partial
+    /// transition function rejecting any 'c'.
+    fn odd_a() -> Dfa<char> {
+        let mut dfa = Dfa::new();
+        let even = dfa.add_state(false);
+        let odd = dfa.add_state(true);
+        dfa.add_transition(even, 'a', odd);
+        dfa.add_transition(odd, 'a', even);
+        dfa.add_transition(even, 'b', even);
+        dfa.add_transition(odd, 'b', odd);
+        dfa
+    }
+
+    #[test]
+    fn test_shortest_word() {
+        assert_eq!(odd_a().shortest_word(), Some(vec!['a']));
+        assert_eq!(Dfa::<char>::new().shortest_word(), None);
+
+        let mut dfa = Dfa::new();
+        let q0 = dfa.add_state(false);
+        let q1 = dfa.add_state(false);
+        let q2 = dfa.add_state(true);
+        dfa.add_transition(q0, 'c', q1);
+        dfa.add_transition(q0, 'b', q1);
+        dfa.add_transition(q1, 'a', q2);
+        assert_eq!(dfa.shortest_word(), Some(vec!['b', 'a']));
+    }
+
+    #[test]
+    fn test_words_shortlex() {
+        let words = odd_a().words(3).collect::<Vec<_>>();
+        let expected = ["a", "ab", "ba", "aaa", "abb", "bab", "bba"]
+            .iter()
+            .map(|w| w.chars().collect::<Vec<_>>())
+            .collect::<Vec<_>>();
+        assert_eq!(words, expected);
+    }
+
+    #[test]
+    fn test_words_agree_with_accepts() {
+        let dfa = odd_a();
+        let expected = generate_words(&['a', 'b', 'c'], 7)
+            .into_iter()
+            .filter(|word| dfa.accepts(word.clone()))
+            .collect::<Vec<_>>();
+        assert_eq!(dfa.words(7).collect::<Vec<_>>(), expected);
+    }
+
+    #[test]
+    fn test_words_empty() {
+        assert_eq!(Dfa::<char>::new().words(5).count(), 0);
+
+        let mut dfa = Dfa::new();
+        let q0 = dfa.add_state(true);
+        dfa.add_transition(q0, 'x', q0);
+        assert_eq!(dfa.words(0).collect::<Vec<_>>(), vec![vec![]]);
+        assert_eq!(dfa.words(2).count(), 3);
+    }
+
+    #[test]
+    fn test_words_huge_max_len() {
+        let mut dfa = Dfa::new();
+        let q0 = dfa.add_state(false);
+        let q1 = dfa.add_state(true);
+        let q2 = dfa.add_state(true);
+        dfa.add_transition(q0, 'a', q1);
+        dfa.add_transition(q1, 'b', q2);
+        assert_eq!(dfa.words(usize::MAX).count(), 2);
+
+        let words = odd_a().words(usize::MAX).take(7).collect::<Vec<_>>();
+        assert_eq!(words, odd_a().words(3).collect::<Vec<_>>());
+    }
+}
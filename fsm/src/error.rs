@@ -0,0 +1,39 @@
+use std::fmt::{self, Display, Formatter};
+
+/// Errors reported by the fallible operations of this crate.
+///
+/// State ids are reported as plain `usize`, regardless of the kind of
+/// automaton they belong to.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Error {
+    /// The id does not refer to a state of the automaton.
+    UnknownState(usize),
+    /// Two states were given the same id.
+    DuplicateState(usize),
+    /// The automaton has no initial state to start from.
+    NoInitialState,
+    /// A machine has no transition from `state` on the input at `position`.
+    MissingTransition { state: usize, position: usize },
+    /// A construction would need more states than the given limit.
+    StateLimitExceeded(usize),
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::UnknownState(id) => write!(f, "unknown state id {}", id),
+            Error::DuplicateState(id) => write!(f, "duplicate state id {}", id),
+            Error::NoInitialState => write!(f, "no initial state"),
+            Error::MissingTransition { state, position } => write!(
+                f,
+                "no transition from state {} on input at position {}",
+                state, position
+            ),
+            Error::StateLimitExceeded(limit) => {
+                write!(f, "more than {} states would be needed", limit)
+            }
+        }
+    }
+}
+
+impl std::error::Error for Error {}
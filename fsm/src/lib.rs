@@ -1,9 +1,14 @@
 pub mod alphabet;
+pub mod automaton;
 pub mod dfa;
+pub mod error;
 pub mod mealy;
 pub mod moore;
 pub mod nfa;
 
+pub use automaton::{Acceptor, Automaton, Transducer};
+pub use error::Error;
+
 pub(crate) mod util;
 
 #[cfg(test)]
@@ -0,0 +1,49 @@
+use std::fmt::Display;
+
+use graphviz_rust::dot_generator::{attr, id};
+use graphviz_rust::dot_structures::{Attribute, Id};
+
+use crate::alphabet::Alphabet;
+use crate::mealy::Mealy;
+use crate::util::graphviz::{quote, render, Style};
+
+impl<I: Alphabet + Display, O: Alphabet + Display> Mealy<I, O> {
+    /// Renders the machine with transitions labelled `input/output`.
+    pub fn render_graphviz(&self) -> String {
+        let style = Style {
+            name: "Mealy",
+            strict: true,
+            start: vec![attr!("shape", "point")],
+        };
+        render(
+            self,
+            style,
+            |_| vec![attr!("shape", "circle")],
+            self.transitions()
+                .map(|(from, input, to, output)| (from.id, (input, output), to.id)),
+            |(input, output)| {
+                let label = format!("{}/{}", input, output);
+                vec![attr!("label", esc quote(&label))]
+            },
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_graphviz() {
+        let mut mealy = Mealy::new();
+        let a = mealy.add_state();
+        let b = mealy.add_state();
+        mealy.add_transition(a, 'x', b, 1);
+        mealy.add_transition(b, 'y', b, 2);
+        let dot = mealy.render_graphviz();
+        assert!(dot.starts_with("strict digraph Mealy {"));
+        assert!(dot.contains("start -> 0 ;"));
+        assert!(dot.contains("0 -> 1 [label=\"x/1\"];"));
+        assert!(dot.contains("1 -> 1 [label=\"y/2\"];"));
+    }
+}
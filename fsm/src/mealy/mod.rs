@@ -1,10 +1,12 @@
-use std::ops::{Index, IndexMut};
-
 use state::{State, StateId};
 
 use crate::alphabet::Alphabet;
+use crate::automaton::{Automaton, Transducer};
+use crate::error::Error;
 use crate::util::arena::Arena;
+use crate::util::machine::impl_states;
 
+pub mod graphviz;
 pub mod state;
 
 #[cfg(feature = "serde")]
@@ -20,41 +22,120 @@ mod serde;
 #[derive(Debug)]
 pub struct Mealy<I: Alphabet, O: Alphabet> {
     states: Arena<State<I, O>>,
+    initial: Option<StateId>,
 }
 
+impl_states!(Mealy<I, O>, State<I, O>, StateId);
+
 impl<I: Alphabet, O: Alphabet> Mealy<I, O> {
-    pub fn new() -> Self {
-        Self {
-            states: Arena::new(),
+    /// Adds a new state. The first state added becomes the initial one.
+    pub fn add_state(&mut self) -> StateId {
+        let id = self.alloc_state(State::new);
+        if self.states.len() == 1 {
+            self.initial = Some(id);
         }
+        id
     }
 
-    pub fn add_state(&mut self) -> StateId {
-        self.states.alloc_with_id(|id| State::new(id))
+    /// Returns the initial state, or `None` if the machine has no states.
+    pub fn initial(&self) -> Option<StateId> {
+        self.initial
     }
 
-    pub fn add_transition(&mut self, from: StateId, input: I, to: StateId, output: O) {
-        self.state_mut(from).add_transition(input, to, output);
+    pub fn set_initial(&mut self, state: StateId) {
+        self.try_set_initial(state)
+            .unwrap_or_else(|e| panic!("{}", e));
+    }
+
+    pub fn try_set_initial(&mut self, state: StateId) -> Result<(), Error> {
+        self.check_state(state)?;
+        self.initial = Some(state);
+        Ok(())
+    }
+
+    /// Removes a state along with all transitions leading into it, and
+    /// returns it.
+    ///
+    /// The id of the removed state is handed out again by the next call to
+    /// [`Mealy::add_state`], after which any copy of it kept by the caller
+    /// silently refers to the new state.
+    ///
+    /// If the state was initial, the machine is left without an initial state;
+    /// a state added later only becomes initial if the machine was empty.
+    pub fn remove_state(&mut self, state: StateId) -> State<I, O> {
+        self.try_remove_state(state)
+            .unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    pub fn try_remove_state(&mut self, state: StateId) -> Result<State<I, O>, Error> {
+        let removed = self.detach_state(state, |to| (to != state).then_some(to))?;
+        if self.initial == Some(state) {
+            self.initial = None;
+        }
+        Ok(removed)
+    }
+
+    pub fn remove_transition(&mut self, from: StateId, input: I) -> Option<(StateId, O)> {
+        self.state_mut(from).remove_transition(input)
+    }
+
+    /// Merges state `b` into state `a`: transitions into `b` are redirected
+    /// to `a`, and `a` inherits the outgoing transitions of `b` on inputs it
+    /// has no transition for. If `b` was initial, `a` becomes initial.
+    /// `b` is removed as by [`Mealy::remove_state`].
+    pub fn merge_states(&mut self, a: StateId, b: StateId) {
+        self.try_merge_states(a, b)
+            .unwrap_or_else(|e| panic!("{}", e));
+    }
+
+    pub fn try_merge_states(&mut self, a: StateId, b: StateId) -> Result<(), Error> {
+        self.check_state(a)?;
+        if a == b {
+            return Ok(());
+        }
+        let redirect = |to| if to == b { a } else { to };
+        let removed = self.detach_state(b, |to| Some(redirect(to)))?;
+        if self.initial == Some(b) {
+            self.initial = Some(a);
+        }
+        let target = self.state_mut(a);
+        for (input, to, output) in removed.transitions() {
+            if target.next(input).is_none() {
+                target.add_transition(input, redirect(to), output);
+            }
+        }
+        Ok(())
     }
 
-    pub fn state(&self, index: StateId) -> &State<I, O> {
-        &self.states[index]
+    /// Renumbers the states to close the gaps left by removed states,
+    /// preserving their relative order.
+    pub fn compact(&mut self) {
+        let old2new = self.compact_states();
+        self.initial = self.initial.map(|s| old2new[&s]);
     }
-    pub fn state_mut(&mut self, index: StateId) -> &mut State<I, O> {
-        &mut self.states[index]
+
+    pub fn add_transition(&mut self, from: StateId, input: I, to: StateId, output: O) {
+        self.try_add_transition(from, input, to, output)
+            .unwrap_or_else(|e| panic!("{}", e));
     }
 
-    pub fn num_states(&self) -> usize {
-        self.states.len()
+    pub fn try_add_transition(
+        &mut self,
+        from: StateId,
+        input: I,
+        to: StateId,
+        output: O,
+    ) -> Result<(), Error> {
+        self.check_state(from)?;
+        self.check_state(to)?;
+        self.state_mut(from).add_transition(input, to, output);
+        Ok(())
     }
 
     pub fn num_transitions(&self) -> usize {
         self.states().map(|state| state.num_transitions()).sum()
     }
 
-    pub fn states(&self) -> impl Iterator<Item = &State<I, O>> {
-        self.states.iter()
-    }
     pub fn states_mut(&mut self) -> impl Iterator<Item = &mut State<I, O>> {
         self.states.iter_mut()
     }
@@ -68,23 +149,34 @@ impl<I: Alphabet, O: Alphabet> Mealy<I, O> {
     }
 }
 
-impl<I: Alphabet, O: Alphabet> Default for Mealy<I, O> {
-    fn default() -> Self {
-        Self::new()
+impl<I: Alphabet, O: Alphabet> Automaton for Mealy<I, O> {
+    type StateId = StateId;
+    type Input = I;
+
+    fn num_states(&self) -> usize {
+        self.num_states()
+    }
+
+    fn state_ids(&self) -> impl Iterator<Item = StateId> + '_ {
+        self.states().map(|state| state.id)
     }
-}
 
-impl<I: Alphabet, O: Alphabet> Index<StateId> for Mealy<I, O> {
-    type Output = State<I, O>;
+    fn initial_state_ids(&self) -> impl Iterator<Item = StateId> + '_ {
+        self.initial.into_iter()
+    }
 
-    fn index(&self, index: StateId) -> &Self::Output {
-        self.state(index)
+    fn successors(&self, state: StateId) -> impl Iterator<Item = (Option<I>, StateId)> + '_ {
+        self.state(state)
+            .transitions()
+            .map(|(input, to, _)| (Some(input), to))
     }
 }
 
-impl<I: Alphabet, O: Alphabet> IndexMut<StateId> for Mealy<I, O> {
-    fn index_mut(&mut self, index: StateId) -> &mut Self::Output {
-        self.state_mut(index)
+impl<I: Alphabet, O: Alphabet> Transducer for Mealy<I, O> {
+    type Output = O;
+
+    fn step(&self, state: StateId, input: I) -> Option<(StateId, O)> {
+        self.next(state, input)
     }
 }
 
@@ -93,17 +185,35 @@ impl<I: Alphabet, O: Alphabet> Mealy<I, O> {
         self.state(current_state).next(input)
     }
 
-    pub fn run<'a, Inputs>(&'a self, inputs: Inputs) -> impl Iterator<Item = (I, StateId, O)> + '_
+    /// Runs the machine from its initial state, yielding for every input the
+    /// state reached and the output produced.
+    ///
+    /// If the machine has no initial state or gets stuck on an input without
+    /// a transition, yields a single error and stops.
+    pub fn run<'a, Inputs>(
+        &'a self,
+        inputs: Inputs,
+    ) -> impl Iterator<Item = Result<(I, StateId, O), Error>> + 'a
     where
         Inputs: IntoIterator<Item = I>,
         <Inputs as IntoIterator>::IntoIter: 'a,
     {
-        let mut current_state = 0;
-        inputs.into_iter().map(move |input: I| {
-            let (next_state, output) = self.next(current_state, input).unwrap();
-            current_state = next_state;
-            (input, current_state, output)
-        })
+        let mut current_state = Some(self.initial.ok_or(Error::NoInitialState));
+        inputs
+            .into_iter()
+            .enumerate()
+            .map_while(move |(position, input)| {
+                let step = current_state.take()?.and_then(|state| {
+                    self.next(state, input).ok_or(Error::MissingTransition {
+                        state: state.index(),
+                        position,
+                    })
+                });
+                if let Ok((next_state, _)) = step {
+                    current_state = Some(Ok(next_state));
+                }
+                Some(step.map(|(next_state, output)| (input, next_state, output)))
+            })
     }
 }
 
@@ -126,10 +236,11 @@ mod tests {
 
         let inputs = vec![0, 1, 1, 0, 0];
         println!("Running Moore machine on {:?}", inputs);
-        let mut current_state = 0;
+        let mut current_state = fsm.initial().unwrap();
         let mut outputs = Vec::new();
         println!("initial state {}", current_state);
-        for (input, new_state, output) in fsm.run(inputs) {
+        for step in fsm.run(inputs) {
+            let (input, new_state, output) = step.unwrap();
             println!(
                 "state {}, input {}, new_state {}, output {}",
                 current_state, input, new_state, output
@@ -139,4 +250,82 @@ mod tests {
         }
         assert_eq!(outputs, vec![false, false, true, false, true]);
     }
+
+    #[test]
+    fn test_mealy_initial() {
+        let mut fsm = Mealy::new();
+        let a = fsm.add_state();
+        let b = fsm.add_state();
+        fsm.add_transition(a, 'x', b, 1);
+        fsm.add_transition(b, 'x', a, 2);
+        assert_eq!(fsm.initial(), Some(a));
+        fsm.set_initial(b);
+        let outputs: Vec<_> = fsm.run("xxx".chars()).map(|step| step.unwrap().2).collect();
+        assert_eq!(outputs, vec![2, 1, 2]);
+    }
+
+    #[test]
+    fn test_mealy_run_errors() {
+        let mut fsm = Mealy::new();
+        assert_eq!(fsm.run([]).count(), 0);
+        assert_eq!(
+            fsm.run(['x']).collect::<Vec<_>>(),
+            vec![Err(Error::NoInitialState)]
+        );
+
+        let a = fsm.add_state();
+        let b = fsm.add_state();
+        fsm.add_transition(a, 'x', b, 0);
+        assert_eq!(
+            fsm.try_add_transition(a, 'y', StateId::new(7), 0),
+            Err(Error::UnknownState(7))
+        );
+        assert_eq!(
+            fsm.try_set_initial(StateId::new(7)),
+            Err(Error::UnknownState(7))
+        );
+        assert_eq!(
+            fsm.try_state(StateId::new(7)).map(|state| state.id),
+            Err(Error::UnknownState(7))
+        );
+        assert_eq!(fsm.try_state_mut(b).map(|state| state.id), Ok(b));
+        let steps: Vec<_> = fsm.run("xxx".chars()).collect();
+        assert_eq!(
+            steps,
+            vec![
+                Ok(('x', b, 0)),
+                Err(Error::MissingTransition {
+                    state: b.index(),
+                    position: 1
+                }),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_mealy_remove_and_merge() {
+        let mut fsm = Mealy::new();
+        let a = fsm.add_state();
+        let b = fsm.add_state();
+        let c = fsm.add_state();
+        fsm.add_transition(a, 'x', b, 1);
+        fsm.add_transition(b, 'x', c, 2);
+        fsm.add_transition(b, 'y', b, 3);
+        fsm.add_transition(c, 'x', a, 1);
+        assert_eq!(fsm.remove_transition(b, 'y'), Some((b, 3)));
+
+        fsm.merge_states(a, c);
+        assert_eq!(fsm.num_states(), 2);
+        let outputs: Vec<_> = fsm
+            .run("xxxx".chars())
+            .map(|step| step.unwrap().2)
+            .collect();
+        assert_eq!(outputs, vec![1, 2, 1, 2]);
+
+        fsm.remove_state(a);
+        assert_eq!(fsm.initial(), None);
+        assert_eq!(fsm.num_transitions(), 0);
+        fsm.compact();
+        assert_eq!(fsm.next(StateId::new(0), 'x'), None);
+    }
 }
@@ -1,28 +1,32 @@
-use std::collections::HashMap;
-
+use serde::de::Error as _;
 use serde::{Deserialize, Deserializer, Serialize, Serializer};
 
 use crate::alphabet::Alphabet;
 use crate::mealy::Mealy;
+use crate::util::serde::{present, Renumbering};
 
-use super::State;
+use super::{State, StateId};
 
 impl<I: Alphabet + Serialize, O: Alphabet + Serialize> Serialize for Mealy<I, O> {
     fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
         #[derive(Serialize)]
         #[serde(rename = "Moore")]
         struct MealyHelper<'a, I: Alphabet, O: Alphabet> {
-            states: Vec<&'a State<I,O>>,
+            initial: Option<StateId>,
+            states: Vec<&'a State<I, O>>,
         }
 
         let helper = MealyHelper {
+            initial: self.initial,
             states: self.states().collect(),
         };
         helper.serialize(serializer)
     }
 }
 
-impl<'de, I: Alphabet + Deserialize<'de>, O: Alphabet + Deserialize<'de>> Deserialize<'de> for Mealy<I, O> {
+impl<'de, I: Alphabet + Deserialize<'de>, O: Alphabet + Deserialize<'de>> Deserialize<'de>
+    for Mealy<I, O>
+{
     fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
     where
         D: Deserializer<'de>,
@@ -30,22 +34,34 @@ impl<'de, I: Alphabet + Deserialize<'de>, O: Alphabet + Deserialize<'de>> Deseri
         #[derive(Deserialize)]
         #[serde(rename = "Moore")]
         struct MealyHelper<I: Alphabet, O: Alphabet> {
+            /// Defaults to the first listed state when absent; `null` means
+            /// no initial state.
+            #[serde(default, deserialize_with = "present")]
+            initial: Option<Option<StateId>>,
             states: Vec<State<I, O>>,
         }
 
         let helper = MealyHelper::deserialize(deserializer)?;
         let mut mealy = Mealy::new();
-        let old2new: HashMap<_, _> = helper
-            .states
-            .iter()
-            .map(|state| (state.id, mealy.add_state()))
-            .collect();
+        let ids = Renumbering::new(
+            helper
+                .states
+                .iter()
+                .map(|state| (state.id, mealy.add_state())),
+        )
+        .map_err(D::Error::custom)?;
+        let new_id = |old: StateId| ids.get(old).map_err(D::Error::custom);
         for old_from_state in &helper.states {
-            let new_from = old2new[&old_from_state.id];
+            let new_from = new_id(old_from_state.id)?;
             for (symbol, old_to, out) in old_from_state.transitions() {
-                mealy.add_transition(new_from, symbol, old2new[&old_to], out);
+                mealy.add_transition(new_from, symbol, new_id(old_to)?, out);
             }
         }
+        match helper.initial {
+            Some(Some(old_initial)) => mealy.set_initial(new_id(old_initial)?),
+            Some(None) => mealy.initial = None,
+            None => {}
+        }
         Ok(mealy)
     }
 }
@@ -58,11 +74,11 @@ mod tests {
 
     #[test]
     fn test_mealy_serde() {
-        let mut mealy = Moore::new();
+        let mut mealy = Mealy::new();
         let a = mealy.add_state();
         let b = mealy.add_state();
         mealy.add_transition(a, '1', a, '2');
-        mealy.add_transition(b, '0', b, '3');
+        mealy.add_transition(b, '1', b, '3');
         mealy.add_transition(a, '0', b, '4');
         mealy.add_transition(b, '0', a, '0');
 
@@ -70,7 +86,23 @@ mod tests {
         let mealy2 = decltype(&mealy, serde_json::from_str(&json).unwrap());
 
         for word in generate_strings(&['0', '1'], 10) {
-            assert_eq!(mealy.accepts(word.chars()), mealy2.accepts(word.chars()));
+            assert_eq!(
+                mealy.run(word.chars()).collect::<Vec<_>>(),
+                mealy2.run(word.chars()).collect::<Vec<_>>()
+            );
         }
     }
+
+    #[test]
+    fn test_mealy_serde_removed_initial() {
+        let mut mealy = Mealy::<char, char>::new();
+        let a = mealy.add_state();
+        mealy.add_state();
+        mealy.remove_state(a);
+
+        let json = serde_json::to_string(&mealy).unwrap();
+        let mealy2 = decltype(&mealy, serde_json::from_str(&json).unwrap());
+        assert_eq!(mealy2.initial(), None);
+        assert_eq!(mealy2.num_states(), 1);
+    }
 }
@@ -1,21 +1,22 @@
-use std::collections::HashMap;
+use std::collections::BTreeMap;
 
 use crate::alphabet::Alphabet;
+use crate::util::id::state_id;
 
-pub type StateId = usize;
+state_id!("Mealy machine");
 
 #[derive(Debug)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct State<I: Alphabet, O: Alphabet> {
     pub id: StateId,
-    transitions: HashMap<I, (StateId, O)>,
+    transitions: BTreeMap<I, (StateId, O)>,
 }
 
 impl<I: Alphabet, O: Alphabet> State<I, O> {
     pub fn new(id: StateId) -> Self {
         Self {
             id,
-            transitions: HashMap::new(),
+            transitions: BTreeMap::new(),
         }
     }
 
@@ -23,6 +24,18 @@ impl<I: Alphabet, O: Alphabet> State<I, O> {
         self.transitions.insert(input, (to, output));
     }
 
+    pub fn remove_transition(&mut self, input: I) -> Option<(StateId, O)> {
+        self.transitions.remove(&input)
+    }
+
+    /// Replaces every target `to` by `f(to)`, dropping transitions for which it is `None`.
+    pub(crate) fn redirect(&mut self, mut f: impl FnMut(StateId) -> Option<StateId>) {
+        self.transitions = std::mem::take(&mut self.transitions)
+            .into_iter()
+            .filter_map(|(input, (to, output))| Some((input, (f(to)?, output))))
+            .collect();
+    }
+
     pub fn num_transitions(&self) -> usize {
         self.transitions.len()
     }
@@ -0,0 +1,48 @@
+use std::fmt::Display;
+
+use graphviz_rust::dot_generator::{attr, id};
+use graphviz_rust::dot_structures::{Attribute, Id};
+
+use crate::alphabet::Alphabet;
+use crate::moore::Moore;
+use crate::util::graphviz::{quote, render, Style};
+
+impl<I: Alphabet + Display, O: Alphabet + Display> Moore<I, O> {
+    /// Renders the machine with states labelled `id/output`.
+    pub fn render_graphviz(&self) -> String {
+        let style = Style {
+            name: "Moore",
+            strict: true,
+            start: vec![attr!("shape", "point")],
+        };
+        render(
+            self,
+            style,
+            |state| {
+                let label = format!("{}/{}", state, self.state(state).output);
+                vec![attr!("shape", "circle"), attr!("label", esc quote(&label))]
+            },
+            self.transitions()
+                .map(|(from, input, to)| (from.id, input, to.id)),
+            |input| vec![attr!("label", esc quote(&input.to_string()))],
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_graphviz() {
+        let mut moore = Moore::new();
+        let a = moore.add_state("off");
+        let b = moore.add_state("on");
+        moore.add_transition(a, "push", b);
+        moore.add_transition(b, "push", a);
+        let dot = moore.render_graphviz();
+        assert!(dot.starts_with("strict digraph Moore {"));
+        assert!(dot.contains("1[shape=circle,label=\"1/on\"];"));
+        assert!(dot.contains("1 -> 0 [label=\"push\"];"));
+    }
+}
@@ -1,10 +1,12 @@
-use std::ops::{Index, IndexMut};
-
 use state::{State, StateId};
 
 use crate::alphabet::Alphabet;
+use crate::automaton::{Automaton, Transducer};
+use crate::error::Error;
 use crate::util::arena::Arena;
+use crate::util::machine::impl_states;
 
+pub mod graphviz;
 pub mod state;
 
 #[cfg(feature = "serde")]
@@ -20,41 +22,120 @@ mod serde;
 #[derive(Debug)]
 pub struct Moore<I: Alphabet, O: Alphabet> {
     states: Arena<State<I, O>>,
+    initial: Option<StateId>,
 }
 
+impl_states!(Moore<I, O>, State<I, O>, StateId);
+
 impl<I: Alphabet, O: Alphabet> Moore<I, O> {
-    pub fn new() -> Self {
-        Self {
-            states: Arena::new(),
+    /// Adds a new state. The first state added becomes the initial one.
+    pub fn add_state(&mut self, output: O) -> StateId {
+        let id = self.alloc_state(|id| State::new(id, output));
+        if self.states.len() == 1 {
+            self.initial = Some(id);
         }
+        id
     }
 
-    pub fn add_state(&mut self, output: O) -> StateId {
-        self.states.alloc_with_id(|id| State::new(id, output))
+    /// Returns the initial state, or `None` if the machine has no states.
+    pub fn initial(&self) -> Option<StateId> {
+        self.initial
     }
 
-    pub fn add_transition(&mut self, from: StateId, input: I, to: StateId) {
-        self.state_mut(from).add_transition(input, to);
+    pub fn set_initial(&mut self, state: StateId) {
+        self.try_set_initial(state)
+            .unwrap_or_else(|e| panic!("{}", e));
+    }
+
+    pub fn try_set_initial(&mut self, state: StateId) -> Result<(), Error> {
+        self.check_state(state)?;
+        self.initial = Some(state);
+        Ok(())
+    }
+
+    /// Removes a state along with all transitions leading into it, and
+    /// returns it.
+    ///
+    /// The id of the removed state is handed out again by the next call to
+    /// [`Moore::add_state`], after which any copy of it kept by the caller
+    /// silently refers to the new state.
+    ///
+    /// If the state was initial, the machine is left without an initial state;
+    /// a state added later only becomes initial if the machine was empty.
+    pub fn remove_state(&mut self, state: StateId) -> State<I, O> {
+        self.try_remove_state(state)
+            .unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    pub fn try_remove_state(&mut self, state: StateId) -> Result<State<I, O>, Error> {
+        let removed = self.detach_state(state, |to| (to != state).then_some(to))?;
+        if self.initial == Some(state) {
+            self.initial = None;
+        }
+        Ok(removed)
+    }
+
+    pub fn remove_transition(&mut self, from: StateId, input: I) -> Option<StateId> {
+        self.state_mut(from).remove_transition(input)
+    }
+
+    /// Merges state `b` into state `a`: transitions into `b` are redirected
+    /// to `a`, and `a` keeps its output and inherits the outgoing transitions
+    /// of `b` on inputs it has no transition for. If `b` was initial, `a`
+    /// becomes initial.
+    /// `b` is removed as by [`Moore::remove_state`].
+    pub fn merge_states(&mut self, a: StateId, b: StateId) {
+        self.try_merge_states(a, b)
+            .unwrap_or_else(|e| panic!("{}", e));
+    }
+
+    pub fn try_merge_states(&mut self, a: StateId, b: StateId) -> Result<(), Error> {
+        self.check_state(a)?;
+        if a == b {
+            return Ok(());
+        }
+        let redirect = |to| if to == b { a } else { to };
+        let removed = self.detach_state(b, |to| Some(redirect(to)))?;
+        if self.initial == Some(b) {
+            self.initial = Some(a);
+        }
+        let target = self.state_mut(a);
+        for (input, to) in removed.transitions() {
+            if target.next(input).is_none() {
+                target.add_transition(input, redirect(to));
+            }
+        }
+        Ok(())
     }
 
-    pub fn state(&self, index: StateId) -> &State<I, O> {
-        &self.states[index]
+    /// Renumbers the states to close the gaps left by removed states,
+    /// preserving their relative order.
+    pub fn compact(&mut self) {
+        let old2new = self.compact_states();
+        self.initial = self.initial.map(|s| old2new[&s]);
     }
-    pub fn state_mut(&mut self, index: StateId) -> &mut State<I, O> {
-        &mut self.states[index]
+
+    pub fn add_transition(&mut self, from: StateId, input: I, to: StateId) {
+        self.try_add_transition(from, input, to)
+            .unwrap_or_else(|e| panic!("{}", e));
     }
 
-    pub fn num_states(&self) -> usize {
-        self.states.len()
+    pub fn try_add_transition(
+        &mut self,
+        from: StateId,
+        input: I,
+        to: StateId,
+    ) -> Result<(), Error> {
+        self.check_state(from)?;
+        self.check_state(to)?;
+        self.state_mut(from).add_transition(input, to);
+        Ok(())
     }
 
     pub fn num_transitions(&self) -> usize {
         self.states().map(|state| state.num_transitions()).sum()
     }
 
-    pub fn states(&self) -> impl Iterator<Item = &State<I, O>> {
-        self.states.iter()
-    }
     pub fn states_mut(&mut self) -> impl Iterator<Item = &mut State<I, O>> {
         self.states.iter_mut()
     }
@@ -68,23 +149,34 @@ impl<I: Alphabet, O: Alphabet> Moore<I, O> {
     }
 }
 
-impl<I: Alphabet, O: Alphabet> Default for Moore<I, O> {
-    fn default() -> Self {
-        Self::new()
+impl<I: Alphabet, O: Alphabet> Automaton for Moore<I, O> {
+    type StateId = StateId;
+    type Input = I;
+
+    fn num_states(&self) -> usize {
+        self.num_states()
+    }
+
+    fn state_ids(&self) -> impl Iterator<Item = StateId> + '_ {
+        self.states().map(|state| state.id)
     }
-}
 
-impl<I: Alphabet, O: Alphabet> Index<StateId> for Moore<I, O> {
-    type Output = State<I, O>;
+    fn initial_state_ids(&self) -> impl Iterator<Item = StateId> + '_ {
+        self.initial.into_iter()
+    }
 
-    fn index(&self, index: StateId) -> &Self::Output {
-        self.state(index)
+    fn successors(&self, state: StateId) -> impl Iterator<Item = (Option<I>, StateId)> + '_ {
+        self.state(state)
+            .transitions()
+            .map(|(input, to)| (Some(input), to))
     }
 }
 
-impl<I: Alphabet, O: Alphabet> IndexMut<StateId> for Moore<I, O> {
-    fn index_mut(&mut self, index: StateId) -> &mut Self::Output {
-        self.state_mut(index)
+impl<I: Alphabet, O: Alphabet> Transducer for Moore<I, O> {
+    type Output = O;
+
+    fn step(&self, state: StateId, input: I) -> Option<(StateId, O)> {
+        self.next(state, input)
     }
 }
 
@@ -95,17 +187,35 @@ impl<I: Alphabet, O: Alphabet> Moore<I, O> {
             .map(|next_state| (next_state, self.state(next_state).output))
     }
 
-    pub fn run<'a, Inputs>(&'a self, inputs: Inputs) -> impl Iterator<Item = (I, StateId, O)> + '_
+    /// Runs the machine from its initial state, yielding for every input the
+    /// state reached and the output produced.
+    ///
+    /// If the machine has no initial state or gets stuck on an input without
+    /// a transition, yields a single error and stops.
+    pub fn run<'a, Inputs>(
+        &'a self,
+        inputs: Inputs,
+    ) -> impl Iterator<Item = Result<(I, StateId, O), Error>> + 'a
     where
         Inputs: IntoIterator<Item = I>,
         <Inputs as IntoIterator>::IntoIter: 'a,
     {
-        let mut current_state = 0;
-        inputs.into_iter().map(move |input: I| {
-            let (next_state, output) = self.next(current_state, input).unwrap();
-            current_state = next_state;
-            (input, current_state, output)
-        })
+        let mut current_state = Some(self.initial.ok_or(Error::NoInitialState));
+        inputs
+            .into_iter()
+            .enumerate()
+            .map_while(move |(position, input)| {
+                let step = current_state.take()?.and_then(|state| {
+                    self.next(state, input).ok_or(Error::MissingTransition {
+                        state: state.index(),
+                        position,
+                    })
+                });
+                if let Ok((next_state, _)) = step {
+                    current_state = Some(Ok(next_state));
+                }
+                Some(step.map(|(next_state, output)| (input, next_state, output)))
+            })
     }
 }
 
@@ -128,10 +238,11 @@ mod tests {
 
         let inputs = vec![1, 0, 1, 0, 1, 1, 0];
         println!("Running Moore machine on {:?}", inputs);
-        let mut current_state = 0;
+        let mut current_state = fsm.initial().unwrap();
         let mut outputs = vec![fsm.state(current_state).output];
         println!("initial state {}, output {}", current_state, outputs[0]);
-        for (input, new_state, output) in fsm.run(inputs) {
+        for step in fsm.run(inputs) {
+            let (input, new_state, output) = step.unwrap();
             println!(
                 "state {}, input {}, new_state {}, output {}",
                 current_state, input, new_state, output
@@ -141,4 +252,73 @@ mod tests {
         }
         assert_eq!(outputs, vec![0, 1, 2, 2, 1, 0, 1, 2]);
     }
+
+    #[test]
+    fn test_moore_initial() {
+        let mut fsm = Moore::new();
+        let a = fsm.add_state(1);
+        let b = fsm.add_state(2);
+        fsm.add_transition(a, 'x', b);
+        fsm.add_transition(b, 'x', a);
+        assert_eq!(fsm.initial(), Some(a));
+        fsm.set_initial(b);
+        let outputs: Vec<_> = fsm.run("xxx".chars()).map(|step| step.unwrap().2).collect();
+        assert_eq!(outputs, vec![1, 2, 1]);
+    }
+
+    #[test]
+    fn test_moore_run_errors() {
+        let mut fsm = Moore::new();
+        let a = fsm.add_state(0);
+        fsm.add_transition(a, 'x', a);
+        let steps: Vec<_> = fsm.run("xy".chars()).collect();
+        assert_eq!(
+            steps,
+            vec![
+                Ok(('x', a, 0)),
+                Err(Error::MissingTransition {
+                    state: a.index(),
+                    position: 1
+                }),
+            ]
+        );
+
+        fsm.remove_state(a);
+        assert_eq!(
+            fsm.run("x".chars()).next(),
+            Some(Err(Error::NoInitialState))
+        );
+        assert_eq!(
+            fsm.try_remove_state(a).unwrap_err(),
+            Error::UnknownState(a.index())
+        );
+        assert_eq!(
+            fsm.try_state(a).map(|state| state.id),
+            Err(Error::UnknownState(a.index()))
+        );
+    }
+
+    #[test]
+    fn test_moore_remove_and_merge() {
+        let mut fsm = Moore::new();
+        let a = fsm.add_state(0);
+        let b = fsm.add_state(1);
+        let c = fsm.add_state(0);
+        fsm.add_transition(a, 'x', b);
+        fsm.add_transition(b, 'x', c);
+        fsm.add_transition(c, 'x', b);
+        fsm.add_transition(c, 'y', a);
+        assert_eq!(fsm.remove_transition(c, 'y'), Some(a));
+
+        fsm.merge_states(a, c);
+        assert_eq!(fsm.num_states(), 2);
+        assert_eq!(fsm.next(b, 'x'), Some((a, 0)));
+        let outputs: Vec<_> = fsm.run("xxx".chars()).map(|step| step.unwrap().2).collect();
+        assert_eq!(outputs, vec![1, 0, 1]);
+
+        fsm.remove_state(b);
+        assert_eq!(fsm.num_transitions(), 0);
+        fsm.compact();
+        assert_eq!(fsm.initial(), Some(StateId::new(0)));
+    }
 }
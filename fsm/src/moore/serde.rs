@@ -1,28 +1,32 @@
-use std::collections::HashMap;
-
+use serde::de::Error as _;
 use serde::{Deserialize, Deserializer, Serialize, Serializer};
 
 use crate::alphabet::Alphabet;
 use crate::moore::Moore;
+use crate::util::serde::{present, Renumbering};
 
-use super::State;
+use super::{State, StateId};
 
 impl<I: Alphabet + Serialize, O: Alphabet + Serialize> Serialize for Moore<I, O> {
     fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
         #[derive(Serialize)]
         #[serde(rename = "Moore")]
         struct MooreHelper<'a, I: Alphabet, O: Alphabet> {
-            states: Vec<&'a State<I,O>>,
+            initial: Option<StateId>,
+            states: Vec<&'a State<I, O>>,
         }
 
         let helper = MooreHelper {
+            initial: self.initial,
             states: self.states().collect(),
         };
         helper.serialize(serializer)
     }
 }
 
-impl<'de, I: Alphabet + Deserialize<'de>, O: Alphabet + Deserialize<'de>> Deserialize<'de> for Moore<I, O> {
+impl<'de, I: Alphabet + Deserialize<'de>, O: Alphabet + Deserialize<'de>> Deserialize<'de>
+    for Moore<I, O>
+{
     fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
     where
         D: Deserializer<'de>,
@@ -30,22 +34,34 @@ impl<'de, I: Alphabet + Deserialize<'de>, O: Alphabet + Deserialize<'de>> Deseri
         #[derive(Deserialize)]
         #[serde(rename = "Moore")]
         struct MooreHelper<I: Alphabet, O: Alphabet> {
+            /// Defaults to the first listed state when absent; `null` means
+            /// no initial state.
+            #[serde(default, deserialize_with = "present")]
+            initial: Option<Option<StateId>>,
             states: Vec<State<I, O>>,
         }
 
         let helper = MooreHelper::deserialize(deserializer)?;
         let mut moore = Moore::new();
-        let old2new: HashMap<_, _> = helper
-            .states
-            .iter()
-            .map(|state| (state.id, moore.add_state(state.output)))
-            .collect();
+        let ids = Renumbering::new(
+            helper
+                .states
+                .iter()
+                .map(|state| (state.id, moore.add_state(state.output))),
+        )
+        .map_err(D::Error::custom)?;
+        let new_id = |old: StateId| ids.get(old).map_err(D::Error::custom);
         for old_from_state in &helper.states {
-            let new_from = old2new[&old_from_state.id];
+            let new_from = new_id(old_from_state.id)?;
             for (symbol, old_to) in old_from_state.transitions() {
-                moore.add_transition(new_from, symbol, old2new[&old_to]);
+                moore.add_transition(new_from, symbol, new_id(old_to)?);
             }
         }
+        match helper.initial {
+            Some(Some(old_initial)) => moore.set_initial(new_id(old_initial)?),
+            Some(None) => moore.initial = None,
+            None => {}
+        }
         Ok(moore)
     }
 }
@@ -62,7 +78,7 @@ mod tests {
         let a = moore.add_state(true);
         let b = moore.add_state(false);
         moore.add_transition(a, '1', a);
-        moore.add_transition(b, '0', b);
+        moore.add_transition(b, '1', b);
         moore.add_transition(a, '0', b);
         moore.add_transition(b, '0', a);
 
@@ -70,7 +86,23 @@ mod tests {
         let moore2 = decltype(&moore, serde_json::from_str(&json).unwrap());
 
         for word in generate_strings(&['0', '1'], 10) {
-            assert_eq!(moore.accepts(word.chars()), moore2.accepts(word.chars()));
+            assert_eq!(
+                moore.run(word.chars()).collect::<Vec<_>>(),
+                moore2.run(word.chars()).collect::<Vec<_>>()
+            );
         }
     }
+
+    #[test]
+    fn test_moore_serde_removed_initial() {
+        let mut moore = Moore::<char, bool>::new();
+        let a = moore.add_state(true);
+        moore.add_state(true);
+        moore.remove_state(a);
+
+        let json = serde_json::to_string(&moore).unwrap();
+        let moore2 = decltype(&moore, serde_json::from_str(&json).unwrap());
+        assert_eq!(moore2.initial(), None);
+        assert_eq!(moore2.num_states(), 1);
+    }
 }
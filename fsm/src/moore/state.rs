@@ -1,15 +1,16 @@
-use std::collections::HashMap;
+use std::collections::BTreeMap;
 
 use crate::alphabet::Alphabet;
+use crate::util::id::state_id;
 
-pub type StateId = usize;
+state_id!("Moore machine");
 
 #[derive(Debug)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct State<I: Alphabet, O: Alphabet> {
     pub id: StateId,
     pub output: O,
-    transitions: HashMap<I, StateId>,
+    transitions: BTreeMap<I, StateId>,
 }
 
 impl<I: Alphabet, O: Alphabet> State<I, O> {
@@ -17,7 +18,7 @@ impl<I: Alphabet, O: Alphabet> State<I, O> {
         Self {
             id,
             output,
-            transitions: HashMap::new(),
+            transitions: BTreeMap::new(),
         }
     }
 
@@ -25,6 +26,18 @@ impl<I: Alphabet, O: Alphabet> State<I, O> {
         self.transitions.insert(input, to);
     }
 
+    pub fn remove_transition(&mut self, input: I) -> Option<StateId> {
+        self.transitions.remove(&input)
+    }
+
+    /// Replaces every target `to` by `f(to)`, dropping transitions for which it is `None`.
+    pub(crate) fn redirect(&mut self, mut f: impl FnMut(StateId) -> Option<StateId>) {
+        self.transitions = std::mem::take(&mut self.transitions)
+            .into_iter()
+            .filter_map(|(input, to)| Some((input, f(to)?)))
+            .collect();
+    }
+
     pub fn num_transitions(&self) -> usize {
         self.transitions.len()
     }
@@ -0,0 +1,239 @@
+use std::collections::{BTreeSet, HashMap, VecDeque};
+
+use crate::alphabet::Alphabet;
+use crate::nfa::state::StateId;
+use crate::nfa::Nfa;
+
+impl<A: Alphabet> Nfa<A> {
+    /// Returns whether the NFA accepts every word over `alphabet`.
+    pub fn is_universal(&self, alphabet: &[A]) -> bool {
+        self.universality_counterexample(alphabet).is_none()
+    }
+
+    /// Returns a shortest word over `alphabet` rejected by the NFA, or `None`
+    /// if it accepts them all.
+    ///
+    /// Uses the forward antichain algorithm (De Wulf, Doyen, Henzinger,
+    /// Raskin) instead of determinization: sets of states are explored
+    /// breadth-first, and a set is discarded as soon as a subset of it has
+    /// already been seen, since the subset is rejecting whenever it is.
+    pub fn universality_counterexample(&self, alphabet: &[A]) -> Option<Vec<A>> {
+        let initial = self.initial_closure();
+        let mut antichain = Antichain::default();
+        antichain.insert(&initial);
+        let mut queue = VecDeque::from([(initial, Vec::new())]);
+
+        while let Some((current, word)) = queue.pop_front() {
+            if !self.any_accepting(current.iter().copied()) {
+                return Some(word);
+            }
+            for &symbol in alphabet {
+                let next = self.post(&current, symbol);
+                if antichain.insert(&next) {
+                    let mut next_word = word.clone();
+                    next_word.push(symbol);
+                    queue.push_back((next, next_word));
+                }
+            }
+        }
+
+        None
+    }
+
+    /// Returns whether every word over `alphabet` accepted by `self` is also
+    /// accepted by `other`.
+    pub fn is_included_in(&self, other: &Nfa<A>, alphabet: &[A]) -> bool {
+        self.inclusion_counterexample(other, alphabet).is_none()
+    }
+
+    /// Returns a shortest word over `alphabet` accepted by `self` but not by
+    /// `other`, or `None` if there is none.
+    ///
+    /// Explores pairs of a state of `self` and a set of states of `other`
+    /// with the same antichain pruning as
+    /// [`Nfa::universality_counterexample`], keeping only the pairs whose set
+    /// of `other` states is minimal.
+    pub fn inclusion_counterexample(&self, other: &Nfa<A>, alphabet: &[A]) -> Option<Vec<A>> {
+        let other_initial = other.initial_closure();
+        let mut antichains: HashMap<StateId, Antichain> = HashMap::new();
+        let mut queue = VecDeque::new();
+        for state in self.initial_closure() {
+            if antichains.entry(state).or_default().insert(&other_initial) {
+                queue.push_back((state, other_initial.clone(), Vec::new()));
+            }
+        }
+
+        while let Some((state, current, word)) = queue.pop_front() {
+            if self.accepting(state) && !other.any_accepting(current.iter().copied()) {
+                return Some(word);
+            }
+            for &symbol in alphabet {
+                let Some(next_states) = self.next(state, symbol) else {
+                    continue;
+                };
+                let next = other.post(&current, symbol);
+                for next_state in self.multi_epsilon_closure(next_states.iter().copied().collect())
+                {
+                    if antichains.entry(next_state).or_default().insert(&next) {
+                        let mut next_word = word.clone();
+                        next_word.push(symbol);
+                        queue.push_back((next_state, next.clone(), next_word));
+                    }
+                }
+            }
+        }
+
+        None
+    }
+}
+
+/// Set of pairwise incomparable (w.r.t. inclusion) minimal sets of states.
+#[derive(Default)]
+struct Antichain {
+    sets: Vec<BTreeSet<StateId>>,
+}
+
+impl Antichain {
+    /// Inserts `set` unless it is subsumed by (is a superset of) some
+    /// element, removing all elements subsumed by `set` in turn.
+    /// Returns whether `set` was inserted.
+    fn insert(&mut self, set: &BTreeSet<StateId>) -> bool {
+        if self.sets.iter().any(|other| other.is_subset(set)) {
+            return false;
+        }
+        self.sets.retain(|other| !set.is_subset(other));
+        self.sets.push(set.clone());
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::test_common::{generate_words, nth_from_end};
+
+    use super::*;
+
+    #[test]
+    fn test_is_universal() {
+        let nfa = nth_from_end(3);
+        let word = nfa.universality_counterexample(&['a', 'b']).unwrap();
+        assert!(!nfa.accepts(word.clone()));
+        assert_eq!(word, vec![]);
+
+        // Words shorter than 3, or whose third-to-last symbol is 'a' or 'b'
+        let mut nfa = Nfa::new();
+        let start = nfa.add_state(true);
+        let short1 = nfa.add_state(true);
+        let short2 = nfa.add_state(true);
+        for symbol in ['a', 'b'] {
+            nfa.add_transition(start, symbol, short1);
+            nfa.add_transition(short1, symbol, short2);
+        }
+        let branch = |nfa: &mut Nfa<char>, third_to_last: char| {
+            let any = nfa.add_state(false);
+            let s1 = nfa.add_state(false);
+            let s2 = nfa.add_state(false);
+            let s3 = nfa.add_state(true);
+            nfa.add_epsilon_transition(start, any);
+            nfa.add_transition(any, third_to_last, s1);
+            for symbol in ['a', 'b'] {
+                nfa.add_transition(any, symbol, any);
+                nfa.add_transition(s1, symbol, s2);
+                nfa.add_transition(s2, symbol, s3);
+            }
+        };
+        branch(&mut nfa, 'a');
+        let word = nfa.universality_counterexample(&['a', 'b']).unwrap();
+        assert!(!nfa.accepts(word.clone()));
+        assert_eq!(word, vec!['b', 'a', 'a']);
+        branch(&mut nfa, 'b');
+        assert!(nfa.is_universal(&['a', 'b']));
+        assert!(!nfa.is_universal(&['a', 'b', 'c']));
+        assert_eq!(
+            nfa.universality_counterexample(&['a', 'b', 'c']),
+            Some(vec!['c'])
+        );
+    }
+
+    #[test]
+    fn test_is_universal_agrees_with_dfa() {
+        let mut nfa = Nfa::new();
+        // Words not containing "aa"
+        let q0 = nfa.add_state(true);
+        let q1 = nfa.add_state(true);
+        nfa.add_transition(q0, 'b', q0);
+        nfa.add_transition(q0, 'a', q1);
+        nfa.add_transition(q1, 'b', q0);
+
+        let alphabet = ['a', 'b'];
+        let dfa = nfa.to_dfa(&alphabet);
+        let word = nfa.universality_counterexample(&alphabet).unwrap();
+        assert!(!nfa.accepts(word.clone()));
+        let dfa_word = dfa.universality_counterexample(&alphabet).unwrap();
+        assert_eq!(word.len(), dfa_word.len());
+
+        // Adding a state accepting all words containing "aa" makes it universal
+        let any = nfa.add_state(false);
+        let seen_a = nfa.add_state(false);
+        let seen_aa = nfa.add_state(true);
+        nfa.add_epsilon_transition(q0, any);
+        nfa.add_transition(any, 'a', any);
+        nfa.add_transition(any, 'b', any);
+        nfa.add_transition(any, 'a', seen_a);
+        nfa.add_transition(seen_a, 'a', seen_aa);
+        nfa.add_transition(seen_aa, 'a', seen_aa);
+        nfa.add_transition(seen_aa, 'b', seen_aa);
+        assert!(nfa.is_universal(&alphabet));
+        assert!(nfa.to_dfa(&alphabet).is_universal(&alphabet));
+    }
+
+    #[test]
+    fn test_is_included_in() {
+        let alphabet = ['a', 'b'];
+        let second = nth_from_end(2);
+        let mut ends_with_ab = Nfa::new();
+        let q0 = ends_with_ab.add_state(false);
+        let q1 = ends_with_ab.add_state(false);
+        let q2 = ends_with_ab.add_state(true);
+        ends_with_ab.add_transition(q0, 'a', q0);
+        ends_with_ab.add_transition(q0, 'b', q0);
+        ends_with_ab.add_transition(q0, 'a', q1);
+        ends_with_ab.add_transition(q1, 'b', q2);
+
+        assert!(ends_with_ab.is_included_in(&second, &alphabet));
+        assert!(!second.is_included_in(&ends_with_ab, &alphabet));
+        let word = second
+            .inclusion_counterexample(&ends_with_ab, &alphabet)
+            .unwrap();
+        assert!(second.accepts(word.clone()));
+        assert!(!ends_with_ab.accepts(word.clone()));
+        assert_eq!(word, vec!['a', 'a']);
+
+        assert!(Nfa::new().is_included_in(&second, &alphabet));
+        assert_eq!(
+            second.inclusion_counterexample(&Nfa::new(), &alphabet),
+            Some(vec!['a', 'a'])
+        );
+    }
+
+    #[test]
+    fn test_is_included_in_agrees_with_accepts() {
+        let alphabet = ['a', 'b'];
+        let candidates = [nth_from_end(1), nth_from_end(2), nth_from_end(3)];
+        for x in &candidates {
+            for y in &candidates {
+                let included = generate_words(&alphabet, 8)
+                    .into_iter()
+                    .all(|word| !x.accepts(word.clone()) || y.accepts(word));
+                assert_eq!(x.is_included_in(y, &alphabet), included);
+                match x.inclusion_counterexample(y, &alphabet) {
+                    None => assert!(included),
+                    Some(word) => {
+                        assert!(!included);
+                        assert!(x.accepts(word.clone()) && !y.accepts(word));
+                    }
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,220 @@
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+use crate::alphabet::Alphabet;
+use crate::automaton::Automaton;
+use crate::nfa::state::StateId;
+use crate::nfa::Nfa;
+use crate::util::partition::coarsest_partition;
+
+/// How ε-transitions are matched by [`Nfa::bisimulation_classes`] and
+/// [`Nfa::bisimilar`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Bisimulation {
+    /// ε is an ordinary label: every transition, including ε-transitions,
+    /// must be matched by a transition with the same label.
+    Strong,
+    /// ε is a silent τ step: a transition on a symbol is matched by one on
+    /// the same symbol preceded and followed by any number of
+    /// ε-transitions, and an ε-transition by any number of them, possibly
+    /// none. A state counts as accepting if its ε-closure contains an
+    /// accepting state.
+    Weak,
+}
+
+impl<A: Alphabet> Nfa<A> {
+    /// Returns the classes of the largest bisimulation between the states of
+    /// the NFA, in increasing order of their smallest state.
+    ///
+    /// Bisimilar states are both accepting or both not, and each transition
+    /// from one is matched by a transition with the same label from the other
+    /// to a bisimilar state. This is finer than language equivalence, as it
+    /// also tells apart when nondeterministic choices are made.
+    pub fn bisimulation_classes(&self, mode: Bisimulation) -> Vec<BTreeSet<StateId>> {
+        let [blocks] = Self::bisimulation_blocks([self], mode);
+        let mut classes: BTreeMap<usize, BTreeSet<StateId>> = BTreeMap::new();
+        for (state, block) in blocks {
+            classes.entry(block).or_default().insert(state);
+        }
+        let mut classes: Vec<_> = classes.into_values().collect();
+        classes.sort_unstable_by_key(|class| class.first().copied());
+        classes
+    }
+
+    /// Returns whether every initial state of `self` is bisimilar to an
+    /// initial state of `other`, and conversely.
+    pub fn bisimilar(&self, other: &Nfa<A>, mode: Bisimulation) -> bool {
+        let [blocks, other_blocks] = Self::bisimulation_blocks([self, other], mode);
+        let initial: BTreeSet<usize> = self.initial.iter().map(|s| blocks[s]).collect();
+        let other_initial: BTreeSet<usize> =
+            other.initial.iter().map(|s| other_blocks[s]).collect();
+        initial == other_initial
+    }
+
+    /// Computes the bisimulation classes of the disjoint union of `nfas`,
+    /// returning the class of each state of each NFA.
+    fn bisimulation_blocks<const N: usize>(
+        nfas: [&Nfa<A>; N],
+        mode: Bisimulation,
+    ) -> [HashMap<StateId, usize>; N] {
+        let mut index: Vec<HashMap<StateId, usize>> = Vec::with_capacity(N);
+        let mut accepting = Vec::new();
+        let mut edges = Vec::new();
+        for nfa in nfas {
+            let offset = accepting.len();
+            let nfa_index: HashMap<StateId, usize> = nfa
+                .state_ids()
+                .enumerate()
+                .map(|(i, state)| (state, offset + i))
+                .collect();
+            for state in nfa.state_ids() {
+                let (state_accepting, transitions) = nfa.labelled_transitions(state, mode);
+                accepting.push(state_accepting);
+                edges.extend(
+                    transitions
+                        .into_iter()
+                        .map(|(label, to)| (nfa_index[&state], label, nfa_index[&to])),
+                );
+            }
+            index.push(nfa_index);
+        }
+
+        let blocks = coarsest_partition(&accepting, &edges);
+        let index: Vec<HashMap<StateId, usize>> = index
+            .into_iter()
+            .map(|nfa_index| {
+                nfa_index
+                    .into_iter()
+                    .map(|(state, i)| (state, blocks[i]))
+                    .collect()
+            })
+            .collect();
+        index.try_into().unwrap()
+    }
+
+    /// Whether `state` counts as accepting, and its transitions as seen by
+    /// the bisimulation, `None` standing for ε.
+    fn labelled_transitions(
+        &self,
+        state: StateId,
+        mode: Bisimulation,
+    ) -> (bool, BTreeSet<(Option<A>, StateId)>) {
+        match mode {
+            Bisimulation::Strong => (self.accepting(state), self.successors(state).collect()),
+            Bisimulation::Weak => {
+                let closure: Vec<StateId> = self.epsilon_closure(state).collect();
+                let mut transitions: BTreeSet<_> = closure.iter().map(|&to| (None, to)).collect();
+                for &from in &closure {
+                    for (symbol, to) in self.state(from).transitions() {
+                        transitions.extend(self.epsilon_closure(to).map(|to| (Some(symbol), to)));
+                    }
+                }
+                (self.any_accepting(closure), transitions)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// `a·(b + c)` or `a·b + a·c`, depending on `late_choice`.
+    fn choice(late_choice: bool) -> Nfa<char> {
+        let mut nfa = Nfa::new();
+        let start = nfa.add_state(false);
+        let end = nfa.add_state(true);
+        let middle = nfa.add_state(false);
+        nfa.add_transition(start, 'a', middle);
+        nfa.add_transition(middle, 'b', end);
+        if late_choice {
+            nfa.add_transition(middle, 'c', end);
+        } else {
+            let other = nfa.add_state(false);
+            nfa.add_transition(start, 'a', other);
+            nfa.add_transition(other, 'c', end);
+        }
+        nfa
+    }
+
+    #[test]
+    fn test_bisimulation_finer_than_language() {
+        let late = choice(true);
+        let early = choice(false);
+        for word in ["ab", "ac", "a", "abc"] {
+            assert_eq!(late.accepts(word.chars()), early.accepts(word.chars()));
+        }
+        assert!(!late.bisimilar(&early, Bisimulation::Strong));
+        assert!(!late.bisimilar(&early, Bisimulation::Weak));
+        assert!(late.bisimilar(&late, Bisimulation::Strong));
+        assert!(early.bisimilar(&choice(false), Bisimulation::Strong));
+    }
+
+    #[test]
+    fn test_bisimulation_classes() {
+        let mut nfa = Nfa::new();
+        // Two copies of a loop on 'a' through an accepting state, and a
+        // state with the same transitions but never accepting
+        let s0 = nfa.add_state(false);
+        let s1 = nfa.add_state(true);
+        let s2 = nfa.add_state(false);
+        let s3 = nfa.add_state(true);
+        let s4 = nfa.add_state(false);
+        nfa.add_transition(s0, 'a', s1);
+        nfa.add_transition(s1, 'a', s2);
+        nfa.add_transition(s2, 'a', s3);
+        nfa.add_transition(s3, 'a', s0);
+        nfa.add_transition(s4, 'a', s4);
+
+        let classes = nfa.bisimulation_classes(Bisimulation::Strong);
+        assert_eq!(
+            classes,
+            vec![
+                BTreeSet::from([s0, s2]),
+                BTreeSet::from([s1, s3]),
+                BTreeSet::from([s4]),
+            ]
+        );
+        assert!(Nfa::<char>::new()
+            .bisimulation_classes(Bisimulation::Strong)
+            .is_empty());
+    }
+
+    #[test]
+    fn test_weak_bisimulation() {
+        // a·ε·b versus a·b
+        let mut with_epsilon = Nfa::new();
+        let s0 = with_epsilon.add_state(false);
+        let s1 = with_epsilon.add_state(false);
+        let s2 = with_epsilon.add_state(false);
+        let s3 = with_epsilon.add_state(true);
+        with_epsilon.add_transition(s0, 'a', s1);
+        with_epsilon.add_epsilon_transition(s1, s2);
+        with_epsilon.add_transition(s2, 'b', s3);
+
+        let mut without = Nfa::new();
+        let t0 = without.add_state(false);
+        let t1 = without.add_state(false);
+        let t2 = without.add_state(true);
+        without.add_transition(t0, 'a', t1);
+        without.add_transition(t1, 'b', t2);
+
+        assert!(!with_epsilon.bisimilar(&without, Bisimulation::Strong));
+        assert!(with_epsilon.bisimilar(&without, Bisimulation::Weak));
+        let classes = with_epsilon.bisimulation_classes(Bisimulation::Weak);
+        assert!(classes.contains(&BTreeSet::from([s1, s2])));
+
+        // A silent choice is still a choice: τ·a·b + τ·a·c versus a·(b + c)
+        let mut silent = Nfa::new();
+        let start = silent.add_state(false);
+        let end = silent.add_state(true);
+        for symbol in ['b', 'c'] {
+            let branch = silent.add_state(false);
+            let middle = silent.add_state(false);
+            silent.add_epsilon_transition(start, branch);
+            silent.add_transition(branch, 'a', middle);
+            silent.add_transition(middle, symbol, end);
+        }
+        assert!(!silent.bisimilar(&choice(true), Bisimulation::Weak));
+        assert!(silent.bisimilar(&silent.clone(), Bisimulation::Weak));
+    }
+}
@@ -0,0 +1,249 @@
+use crate::alphabet::Alphabet;
+use crate::dfa::Dfa;
+use crate::error::Error;
+use crate::nfa::state::StateId;
+use crate::nfa::subset::{self, Subsets};
+use crate::nfa::Nfa;
+use crate::util::bitset::BitSet;
+
+/// NFA compiled for bit-parallel simulation.
+///
+/// Sets of states are bitsets indexed by state id. For every symbol and
+/// state, the ε-closed set of successors is precomputed as a mask, so a
+/// step is the union of the masks of the current states. Memory grows with
+/// the square of the number of states, which suits NFAs of up to a few
+/// thousand states.
+#[derive(Debug, Clone)]
+pub struct BitNfa<A: Alphabet> {
+    /// Sorted, so that the column of a symbol is its position.
+    alphabet: Vec<A>,
+    /// `successors[column][state]`, empty for states without transitions.
+    successors: Vec<Vec<BitSet>>,
+    initial: BitSet,
+    accepting: BitSet,
+    capacity: usize,
+}
+
+impl<A: Alphabet> Nfa<A> {
+    /// Compiles the NFA into a [`BitNfa`].
+    pub fn to_bit_nfa(&self) -> BitNfa<A> {
+        let capacity = self.states.id_bound();
+        let closure = |start: Vec<StateId>| {
+            let mut set = BitSet::new(capacity);
+            for state in self.multi_epsilon_closure(start) {
+                set.insert(state.index());
+            }
+            set
+        };
+
+        let alphabet = self.symbols();
+        let mut successors = vec![vec![BitSet::default(); capacity]; alphabet.len()];
+        for state in self.states() {
+            for (column, &symbol) in alphabet.iter().enumerate() {
+                if let Some(targets) = state.next(symbol) {
+                    successors[column][state.id.index()] =
+                        closure(targets.iter().copied().collect());
+                }
+            }
+        }
+
+        let mut accepting = BitSet::new(capacity);
+        for state in self.states().filter(|state| state.accepting) {
+            accepting.insert(state.id.index());
+        }
+
+        BitNfa {
+            initial: closure(self.initial.iter().copied().collect()),
+            alphabet,
+            successors,
+            accepting,
+            capacity,
+        }
+    }
+}
+
+impl<A: Alphabet> BitNfa<A> {
+    /// Returns the symbols the NFA has transitions on, in increasing order.
+    pub fn alphabet(&self) -> &[A] {
+        &self.alphabet
+    }
+
+    /// Returns an exclusive upper bound on the state ids.
+    pub fn id_bound(&self) -> usize {
+        self.capacity
+    }
+
+    /// Successors of `states` on `symbol`, closed under ε-transitions.
+    fn post(&self, states: &BitSet, symbol: A) -> BitSet {
+        let mut next = BitSet::new(self.capacity);
+        if let Ok(column) = self.alphabet.binary_search(&symbol) {
+            let successors = &self.successors[column];
+            for state in states.iter() {
+                next.union_with(&successors[state]);
+            }
+        }
+        next
+    }
+
+    pub fn accepts(&self, word: impl IntoIterator<Item = A>) -> bool {
+        let mut current = self.initial.clone();
+        for symbol in word {
+            current = self.post(&current, symbol);
+            if current.is_empty() {
+                return false;
+            }
+        }
+        current.intersects(&self.accepting)
+    }
+
+    /// Subset construction over `alphabet`, see [`Nfa::to_dfa`].
+    pub fn to_dfa(&self, alphabet: &[A]) -> Dfa<A> {
+        let (dfa, _) = self
+            .subset_construction(alphabet, None)
+            .expect("no state limit");
+        dfa
+    }
+
+    /// Subset construction over `alphabet`, see [`Nfa::to_dfa_with_map`].
+    pub(super) fn subset_construction(
+        &self,
+        alphabet: &[A],
+        limit: Option<usize>,
+    ) -> Result<(Dfa<A>, Vec<BitSet>), Error> {
+        subset::subset_construction(self, alphabet, limit)
+    }
+}
+
+impl<A: Alphabet> Subsets<A> for BitNfa<A> {
+    type Set = BitSet;
+
+    fn initial(&self) -> Self::Set {
+        self.initial.clone()
+    }
+
+    fn post(&self, states: &Self::Set, symbol: A) -> Self::Set {
+        self.post(states, symbol)
+    }
+
+    fn is_empty(&self, states: &Self::Set) -> bool {
+        states.is_empty()
+    }
+
+    fn any_accepting(&self, states: &Self::Set) -> bool {
+        states.intersects(&self.accepting)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::rngs::StdRng;
+    use rand::{Rng, SeedableRng};
+
+    use crate::nfa::state::StateId;
+    use crate::nfa::{LazyDfa, BIT_NFA_MAX_STATES};
+    use crate::test_common::{generate_words, nth_from_end};
+
+    use super::*;
+
+    /// NFA over {a, b, c} with random transitions, an ε-cycle, several
+    /// initial states and some removed states.
+    fn random_nfa(rng: &mut StdRng, num_states: usize) -> Nfa<char> {
+        let mut nfa = Nfa::new();
+        let states: Vec<_> = (0..num_states)
+            .map(|_| nfa.add_state(rng.gen_bool(0.3)))
+            .collect();
+        for &from in &states {
+            for &to in &states {
+                for symbol in ['a', 'b', 'c'] {
+                    if rng.gen_bool(0.15) {
+                        nfa.add_transition(from, symbol, to);
+                    }
+                }
+                if rng.gen_bool(0.1) {
+                    nfa.add_epsilon_transition(from, to);
+                }
+            }
+        }
+        nfa.add_epsilon_transition(states[0], states[1]);
+        nfa.add_epsilon_transition(states[1], states[0]);
+        nfa.set_initial_states([states[0], states[2]]);
+        for &state in &states[3..] {
+            if rng.gen_bool(0.3) {
+                nfa.add_initial(state);
+            }
+            if rng.gen_bool(0.2) {
+                nfa.remove_state(state);
+            }
+        }
+        nfa
+    }
+
+    #[test]
+    fn test_bit_nfa_to_dfa_agrees_with_sets() {
+        let mut rng = StdRng::seed_from_u64(22);
+        let alphabet = ['a', 'b', 'c'];
+        for num_states in 3..9 {
+            for _ in 0..10 {
+                let nfa = random_nfa(&mut rng, num_states);
+                let bit_dfa = nfa.to_bit_nfa().to_dfa(&alphabet);
+                let (set_dfa, _) = nfa.subset_construction(&alphabet, None).unwrap();
+                assert_eq!(bit_dfa.num_states(), set_dfa.num_states());
+                for word in generate_words(&alphabet, 4) {
+                    let expected = nfa.accepts_by_sets(word.clone());
+                    assert_eq!(bit_dfa.accepts(word.clone()), expected, "{:?}", word);
+                    assert_eq!(set_dfa.accepts(word.clone()), expected);
+                    assert_eq!(nfa.accepts(word), expected);
+                }
+            }
+        }
+
+        // Above the threshold, to_dfa works on sets of state ids
+        let mut large = nth_from_end(3);
+        for _ in 0..BIT_NFA_MAX_STATES {
+            large.add_state(false);
+        }
+        let dfa = large.to_dfa(&['a', 'b']);
+        assert_eq!(dfa.num_states(), 8);
+        for word in generate_words(&['a', 'b'], 6) {
+            assert_eq!(dfa.accepts(word.clone()), large.accepts(word));
+        }
+    }
+
+    #[test]
+    fn test_bit_nfa_agrees_with_accepts() {
+        // Words whose third symbol from the end is 'a', with detours reading
+        // "cc" through more than 64 states
+        let mut nfa = nth_from_end(3);
+        let start = StateId::new(0);
+        let padding: Vec<_> = (0..70).map(|_| nfa.add_state(false)).collect();
+        let c1 = nfa.add_state(false);
+        let c2 = nfa.add_state(true);
+        nfa.add_epsilon_transition(start, padding[0]);
+        for pair in padding.windows(2) {
+            nfa.add_epsilon_transition(pair[0], pair[1]);
+        }
+        nfa.add_transition(padding[69], 'c', c1);
+        nfa.add_transition(c1, 'c', c2);
+        nfa.add_epsilon_transition(c2, start);
+        nfa.remove_state(padding[35]);
+        nfa.add_epsilon_transition(padding[34], padding[36]);
+
+        // LazyDfa still works on sets of state ids
+        let bit_nfa = nfa.to_bit_nfa();
+        let mut lazy = LazyDfa::new(&nfa);
+        assert_eq!(bit_nfa.alphabet(), &['a', 'b', 'c']);
+        assert_eq!(bit_nfa.id_bound(), 76);
+        for word in generate_words(&['a', 'b', 'c', 'd'], 6) {
+            let expected = lazy.accepts(word.clone());
+            assert_eq!(bit_nfa.accepts(word.clone()), expected, "{:?}", word);
+            assert_eq!(nfa.accepts(word.clone()), expected, "{:?}", word);
+        }
+    }
+
+    #[test]
+    fn test_bit_nfa_empty() {
+        let nfa = Nfa::<char>::new();
+        let bit_nfa = nfa.to_bit_nfa();
+        assert!(!bit_nfa.accepts([]));
+    }
+}
@@ -0,0 +1,79 @@
+use std::collections::BTreeSet;
+
+use crate::alphabet::Alphabet;
+use crate::nfa::Nfa;
+
+impl<A: Alphabet> Nfa<A> {
+    /// Returns an equivalent NFA without ε-transitions, keeping the same state ids.
+    ///
+    /// Each state gets the transitions of every state in its ε-closure, and
+    /// becomes accepting if its closure contains an accepting state. States
+    /// that were only reachable through ε-transitions may become unreachable;
+    /// use [`Nfa::trim`] to drop them.
+    pub fn remove_epsilons(&self) -> Nfa<A> {
+        let mut nfa = self.clone();
+        for state in self.states() {
+            let closure: Vec<_> = self.epsilon_closure(state.id).collect();
+            let new_state = nfa.state_mut(state.id);
+            new_state.accepting = self.any_accepting(closure.iter().copied());
+            for &to in state.next_epsilon() {
+                new_state.remove_epsilon_transition(to);
+            }
+            let transitions: BTreeSet<_> = closure
+                .into_iter()
+                .flat_map(|s| self.state(s).transitions())
+                .collect();
+            for (symbol, to) in transitions {
+                if !state.next(symbol).is_some_and(|next| next.contains(&to)) {
+                    new_state.add_transition(symbol, to);
+                }
+            }
+        }
+        nfa
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::test_common::{generate_words, word};
+
+    use super::*;
+
+    #[test]
+    fn test_remove_epsilons() {
+        let mut nfa = Nfa::new();
+        // a*b*c*
+        let a = nfa.add_state(false);
+        let b = nfa.add_state(false);
+        let c = nfa.add_state(true);
+        nfa.add_transition(a, 'a', a);
+        nfa.add_transition(b, 'b', b);
+        nfa.add_transition(c, 'c', c);
+        nfa.add_epsilon_transition(a, b);
+        nfa.add_epsilon_transition(b, c);
+        nfa.add_epsilon_transition(c, a);
+
+        let removed = nfa.remove_epsilons();
+        assert_eq!(removed.num_states(), nfa.num_states());
+        assert_eq!(removed.num_epsilon_transitions(), 0);
+        assert!(removed.accepting(a) && removed.accepting(b));
+        for word in generate_words(&['a', 'b', 'c'], 6) {
+            assert_eq!(removed.accepts(word.clone()), nfa.accepts(word));
+        }
+    }
+
+    #[test]
+    fn test_remove_epsilons_regular_operations() {
+        // (a|bc)*b?
+        let nfa = word(['a'])
+            .union(word(['b']).concat(word(['c'])))
+            .star()
+            .concat(word(['b']).optional());
+
+        let removed = nfa.remove_epsilons();
+        assert_eq!(removed.num_epsilon_transitions(), 0);
+        for word in generate_words(&['a', 'b', 'c'], 7) {
+            assert_eq!(removed.accepts(word.clone()), nfa.accepts(word));
+        }
+    }
+}
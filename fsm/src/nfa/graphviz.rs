@@ -1,67 +1,42 @@
 use std::fmt::Display;
 
-use graphviz_rust::dot_generator::{attr, edge, graph, id, node, node_id, stmt};
-use graphviz_rust::dot_structures::{
-    Attribute, Edge, EdgeTy, Graph, Id, Node, NodeId, Stmt, Vertex,
-};
-use graphviz_rust::printer::{DotPrinter, PrinterContext};
+use graphviz_rust::dot_generator::{attr, id};
+use graphviz_rust::dot_structures::{Attribute, Id};
 
 use crate::alphabet::Alphabet;
 use crate::nfa::Nfa;
+use crate::util::graphviz::{render, Style};
 
 impl<A: Alphabet + Display> Nfa<A> {
     pub fn render_graphviz(&self) -> String {
-        let mut stmts = Vec::new();
-
-        stmts.push(stmt!(attr!("rankdir", "LR")));
-
-        // States:
-        for state in self.states() {
-            let name = format!("{}", state.id);
-            let attr = attr!(
-                "shape",
-                if state.accepting {
+        let style = Style {
+            name: "NFA",
+            strict: false,
+            start: vec![attr!("shape", "point"), attr!("width", "0")],
+        };
+        let transitions = self
+            .transitions()
+            .map(|(from, symbol, to)| (from.id, Some(symbol), to.id));
+        let epsilon_transitions = self
+            .epsilon_transitions()
+            .map(|(from, to)| (from.id, None, to.id));
+        render(
+            self,
+            style,
+            |state| {
+                let shape = if self.accepting(state) {
                     "doublecircle"
                 } else {
                     "circle"
-                }
-            );
-            let node = node!( name; attr );
-            stmts.push(stmt!(node));
-        }
-
-        // Initial state:
-        if !self.states.is_empty() {
-            let attrs = vec![attr!("shape", "point"), attr!("width", "0")];
-            let node = node!("start", attrs);
-            stmts.push(stmt!(node));
-            let edge = edge!( node_id!("start") => node_id!(0) );
-            stmts.push(stmt!(edge));
-        }
-
-        // Transitions:
-        for (from, symbol, to) in self.transitions() {
-            let from = format!("{}", from.id);
-            let to = format!("{}", to.id);
-            let symbol = format!("{}", symbol);
-            let attr = attr!("label", symbol);
-            let edge = edge!( node_id!(from) => node_id!(to); attr );
-            stmts.push(stmt!(edge));
-        }
-
-        // ε-Transitions:
-        for (from, to) in self.epsilon_transitions() {
-            let from = format!("{}", from.id);
-            let to = format!("{}", to.id);
-            let attr = attr!("label", esc "ε");
-            let edge = edge!( node_id!(from) => node_id!(to); attr );
-            stmts.push(stmt!(edge));
-        }
-
-        let g = graph!( di id!("NFA"), stmts );
-        let mut ctx = PrinterContext::default();
-        ctx.with_semi();
-        g.print(&mut ctx)
+                };
+                vec![attr!("shape", shape)]
+            },
+            transitions.chain(epsilon_transitions),
+            |symbol| match symbol {
+                Some(symbol) => vec![attr!("label", symbol)],
+                None => vec![attr!("label", esc "ε")],
+            },
+        )
     }
 
     // pub fn render_graphviz(&self) -> String {
@@ -107,3 +82,33 @@ impl<A: Alphabet + Display> Nfa<A> {
     //     Ok(())
     // }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_graphviz_sorted() {
+        let mut nfa = Nfa::new();
+        let a = nfa.add_state(false);
+        let b = nfa.add_state(true);
+        nfa.add_transition(a, 'b', b);
+        nfa.add_transition(a, 'a', b);
+        nfa.add_transition(a, 'a', a);
+        nfa.add_epsilon_transition(b, a);
+
+        let expected = "\
+digraph NFA {
+    rankdir=LR;
+    0[shape=circle];
+    1[shape=doublecircle];
+    start[shape=point,width=0];
+    start -> 0 ;
+    0 -> 0 [label=a];
+    0 -> 1 [label=a];
+    0 -> 1 [label=b];
+    1 -> 0 [label=\"ε\"];
+}";
+        assert_eq!(nfa.render_graphviz(), expected);
+    }
+}
@@ -0,0 +1,198 @@
+use std::collections::{BTreeSet, HashMap};
+
+use crate::alphabet::Alphabet;
+use crate::nfa::state::StateId;
+use crate::nfa::Nfa;
+
+/// Determinized state: an ε-closed set of NFA states.
+struct CachedState<A> {
+    states: BTreeSet<StateId>,
+    accepting: bool,
+    /// Known successors, `None` being the dead state.
+    transitions: HashMap<A, Option<usize>>,
+}
+
+/// DFA built on the fly from an [`Nfa`] while reading input.
+///
+/// Determinized states are only computed when a run reaches them, and are
+/// cached together with the transitions taken so far. The cache holds at
+/// most `capacity` states; when it is full it is flushed, and states needed
+/// afterwards are rebuilt from the NFA. The run is incremental: [`step`]
+/// feeds one symbol to the current state, and [`reset`] goes back to the
+/// initial one.
+///
+/// [`step`]: LazyDfa::step
+/// [`reset`]: LazyDfa::reset
+pub struct LazyDfa<'a, A: Alphabet> {
+    nfa: &'a Nfa<A>,
+    capacity: usize,
+    cache: Vec<CachedState<A>>,
+    index: HashMap<BTreeSet<StateId>, usize>,
+    /// `None` once the run can no longer accept.
+    current: Option<usize>,
+    flushes: usize,
+}
+
+impl<'a, A: Alphabet> LazyDfa<'a, A> {
+    const DEFAULT_CAPACITY: usize = 4096;
+
+    pub fn new(nfa: &'a Nfa<A>) -> Self {
+        Self::with_capacity(nfa, Self::DEFAULT_CAPACITY)
+    }
+
+    /// Creates a lazy DFA caching at most `capacity` determinized states
+    /// (and at least one).
+    pub fn with_capacity(nfa: &'a Nfa<A>, capacity: usize) -> Self {
+        let mut lazy = Self {
+            nfa,
+            capacity: capacity.max(1),
+            cache: Vec::new(),
+            index: HashMap::new(),
+            current: None,
+            flushes: 0,
+        };
+        lazy.reset();
+        lazy
+    }
+
+    /// Restarts the run from the ε-closure of the initial states.
+    pub fn reset(&mut self) {
+        let initial = self.nfa.initial_closure();
+        self.current = (!initial.is_empty()).then(|| self.intern(initial));
+    }
+
+    /// Reads `symbol`, returning `false` if no continuation of the input
+    /// read since the last reset can be accepted anymore.
+    pub fn step(&mut self, symbol: A) -> bool {
+        let Some(current) = self.current else {
+            return false;
+        };
+        self.current = match self.cache[current].transitions.get(&symbol) {
+            Some(&next) => next,
+            None => {
+                let next_states = self.nfa.post(&self.cache[current].states, symbol);
+                let flushes = self.flushes;
+                let next = (!next_states.is_empty()).then(|| self.intern(next_states));
+                // A flush invalidates `current`, so the transition is only
+                // recorded if the cache survived.
+                if self.flushes == flushes {
+                    self.cache[current].transitions.insert(symbol, next);
+                }
+                next
+            }
+        };
+        self.current.is_some()
+    }
+
+    /// Returns whether the input read since the last reset is accepted.
+    pub fn is_accepting(&self) -> bool {
+        self.current
+            .is_some_and(|current| self.cache[current].accepting)
+    }
+
+    /// Returns the NFA states the run is in, empty once it is dead.
+    pub fn current_states(&self) -> BTreeSet<StateId> {
+        self.current
+            .map(|current| self.cache[current].states.clone())
+            .unwrap_or_default()
+    }
+
+    /// Resets the run and reads `word`, stopping early once it is dead.
+    pub fn accepts(&mut self, word: impl IntoIterator<Item = A>) -> bool {
+        self.reset();
+        for symbol in word {
+            if !self.step(symbol) {
+                return false;
+            }
+        }
+        self.is_accepting()
+    }
+
+    pub fn num_cached_states(&self) -> usize {
+        self.cache.len()
+    }
+
+    /// Returns how many times the cache was flushed for lack of room.
+    pub fn num_flushes(&self) -> usize {
+        self.flushes
+    }
+
+    /// Returns the cache slot of `states`, adding it (and flushing the
+    /// cache first if it is full) if needed.
+    fn intern(&mut self, states: BTreeSet<StateId>) -> usize {
+        if let Some(&id) = self.index.get(&states) {
+            return id;
+        }
+        if self.cache.len() >= self.capacity {
+            self.cache.clear();
+            self.index.clear();
+            self.flushes += 1;
+        }
+        let id = self.cache.len();
+        self.index.insert(states.clone(), id);
+        self.cache.push(CachedState {
+            accepting: self.nfa.any_accepting(states.iter().copied()),
+            states,
+            transitions: HashMap::new(),
+        });
+        id
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::test_common::{generate_words, nth_from_end};
+
+    use super::*;
+
+    #[test]
+    fn test_lazy_agrees_with_nfa() {
+        let nfa = nth_from_end(4);
+        let mut lazy = LazyDfa::new(&nfa);
+        for word in generate_words(&['a', 'b', 'c'], 7) {
+            assert_eq!(lazy.accepts(word.clone()), nfa.accepts(word));
+        }
+        assert_eq!(lazy.num_cached_states(), 16);
+        assert_eq!(lazy.num_flushes(), 0);
+    }
+
+    #[test]
+    fn test_lazy_bounded_cache() {
+        let nfa = nth_from_end(5);
+        let mut lazy = LazyDfa::with_capacity(&nfa, 4);
+        for word in generate_words(&['a', 'b'], 9) {
+            assert_eq!(lazy.accepts(word.clone()), nfa.accepts(word));
+            assert!(lazy.num_cached_states() <= 4);
+        }
+        assert!(lazy.num_flushes() > 0);
+    }
+
+    #[test]
+    fn test_lazy_step() {
+        let mut nfa = Nfa::new();
+        let a = nfa.add_state(false);
+        let b = nfa.add_state(false);
+        let c = nfa.add_state(true);
+        nfa.add_epsilon_transition(a, b);
+        nfa.add_transition(b, 'x', c);
+        nfa.add_transition(c, 'y', b);
+
+        let mut lazy = LazyDfa::new(&nfa);
+        assert_eq!(lazy.current_states(), BTreeSet::from([a, b]));
+        assert!(!lazy.is_accepting());
+        assert!(lazy.step('x'));
+        assert!(lazy.is_accepting());
+        assert!(lazy.step('y'));
+        assert_eq!(lazy.current_states(), BTreeSet::from([b]));
+        assert!(!lazy.step('y'));
+        assert!(!lazy.step('x'));
+        assert!(lazy.current_states().is_empty());
+
+        lazy.reset();
+        assert!(lazy.step('x'));
+        assert!(lazy.is_accepting());
+
+        let empty = Nfa::<char>::new();
+        assert!(!LazyDfa::new(&empty).accepts([]));
+    }
+}
@@ -1,52 +1,197 @@
-use std::collections::{BTreeSet, HashMap, HashSet};
-use std::ops::{Index, IndexMut};
+use std::collections::BTreeSet;
 
+pub use bisimulation::Bisimulation;
+pub use bit::BitNfa;
+pub use lazy::LazyDfa;
 use state::{State, StateId};
 
 use crate::alphabet::Alphabet;
+use crate::automaton::{Acceptor, Automaton, Build};
 use crate::dfa::Dfa;
+use crate::error::Error;
 use crate::util::arena::Arena;
 use crate::util::dfs::multi_dfs;
+use crate::util::machine::impl_states;
 
+mod antichain;
+mod bisimulation;
+mod bit;
+mod epsilon;
 pub mod graphviz;
+mod lazy;
+mod ops;
+mod regex;
+mod simulation;
 pub mod state;
+mod subset;
+mod trim;
+mod words;
 
 #[cfg(feature = "serde")]
 mod serde;
 
-#[derive(Debug)]
+/// Number of state ids up to which [`Nfa::to_dfa`] runs on the [`BitNfa`]
+/// form, whose successor masks take memory quadratic in it.
+const BIT_NFA_MAX_STATES: usize = 4096;
+
+#[derive(Debug, Clone)]
 pub struct Nfa<A: Alphabet> {
     states: Arena<State<A>>,
+    initial: BTreeSet<StateId>,
 }
 
+impl_states!(Nfa<A>, State<A>, StateId);
+
 impl<A: Alphabet> Nfa<A> {
-    pub fn new() -> Self {
-        Self {
-            states: Arena::new(),
+    /// Adds a new state. The first state added becomes the initial one.
+    pub fn add_state(&mut self, accepting: bool) -> StateId {
+        let id = self.alloc_state(|id| State::new(id, accepting));
+        if self.states.len() == 1 {
+            self.initial.insert(id);
         }
+        id
     }
 
-    pub fn add_state(&mut self, accepting: bool) -> StateId {
-        self.states.alloc_with_id(|id| State::new(id, accepting))
+    pub fn initial_states(&self) -> &BTreeSet<StateId> {
+        &self.initial
     }
 
-    pub fn add_transition(&mut self, from: StateId, symbol: A, to: StateId) {
-        self.state_mut(from).add_transition(symbol, to);
+    /// Makes `state` the only initial state.
+    pub fn set_initial(&mut self, state: StateId) {
+        self.set_initial_states([state]);
     }
 
-    pub fn add_epsilon_transition(&mut self, from: StateId, to: StateId) {
-        self.state_mut(from).add_epsilon_transition(to);
+    pub fn set_initial_states(&mut self, states: impl IntoIterator<Item = StateId>) {
+        self.try_set_initial_states(states)
+            .unwrap_or_else(|e| panic!("{}", e));
+    }
+
+    /// Replaces the initial states, leaving them unchanged if any of
+    /// `states` is unknown.
+    pub fn try_set_initial_states(
+        &mut self,
+        states: impl IntoIterator<Item = StateId>,
+    ) -> Result<(), Error> {
+        let states = states.into_iter().collect::<BTreeSet<_>>();
+        for &state in &states {
+            self.check_state(state)?;
+        }
+        self.initial = states;
+        Ok(())
     }
 
-    pub fn state(&self, index: StateId) -> &State<A> {
-        &self.states[index]
+    pub fn add_initial(&mut self, state: StateId) {
+        self.try_add_initial(state)
+            .unwrap_or_else(|e| panic!("{}", e));
     }
-    pub fn state_mut(&mut self, index: StateId) -> &mut State<A> {
-        &mut self.states[index]
+
+    pub fn try_add_initial(&mut self, state: StateId) -> Result<(), Error> {
+        self.check_state(state)?;
+        self.initial.insert(state);
+        Ok(())
+    }
+
+    /// Removes a state along with all transitions leading into it, and
+    /// returns it.
+    ///
+    /// The id of the removed state is handed out again by the next call to
+    /// [`Nfa::add_state`], after which any copy of it kept by the caller
+    /// silently refers to the new state.
+    ///
+    /// If the state was initial, it is dropped from the initial states;
+    /// a state added later only becomes initial if the machine was empty.
+    pub fn remove_state(&mut self, state: StateId) -> State<A> {
+        self.try_remove_state(state)
+            .unwrap_or_else(|e| panic!("{}", e))
     }
 
-    pub fn num_states(&self) -> usize {
-        self.states.len()
+    pub fn try_remove_state(&mut self, state: StateId) -> Result<State<A>, Error> {
+        let removed = self.detach_state(state, |to| (to != state).then_some(to))?;
+        self.initial.remove(&state);
+        Ok(removed)
+    }
+
+    /// Removes the transition on `symbol` from `from` to `to`, returning whether it existed.
+    pub fn remove_transition(&mut self, from: StateId, symbol: A, to: StateId) -> bool {
+        self.state_mut(from).remove_transition(symbol, to)
+    }
+
+    /// Removes the ε-transition from `from` to `to`, returning whether it existed.
+    pub fn remove_epsilon_transition(&mut self, from: StateId, to: StateId) -> bool {
+        self.state_mut(from).remove_epsilon_transition(to)
+    }
+
+    /// Merges state `b` into state `a`: transitions into `b` are redirected
+    /// to `a`, `a` inherits all outgoing transitions of `b` and becomes
+    /// accepting if `b` was. If `b` was initial, `a` becomes initial.
+    /// ε-loops on `a` are dropped, since they have no effect.
+    /// `b` is removed as by [`Nfa::remove_state`].
+    pub fn merge_states(&mut self, a: StateId, b: StateId) {
+        self.try_merge_states(a, b)
+            .unwrap_or_else(|e| panic!("{}", e));
+    }
+
+    pub fn try_merge_states(&mut self, a: StateId, b: StateId) -> Result<(), Error> {
+        self.check_state(a)?;
+        if a == b {
+            return Ok(());
+        }
+        let redirect = |to| if to == b { a } else { to };
+        let removed = self.detach_state(b, |to| Some(redirect(to)))?;
+        if self.initial.remove(&b) {
+            self.initial.insert(a);
+        }
+        let target = self.state_mut(a);
+        target.accepting |= removed.accepting;
+        for (symbol, to) in removed.transitions() {
+            if !target
+                .next(symbol)
+                .is_some_and(|next| next.contains(&redirect(to)))
+            {
+                target.add_transition(symbol, redirect(to));
+            }
+        }
+        for &to in removed.next_epsilon() {
+            target.add_epsilon_transition(redirect(to));
+        }
+        target.remove_epsilon_transition(a);
+        Ok(())
+    }
+
+    /// Renumbers the states to close the gaps left by removed states,
+    /// preserving their relative order.
+    pub fn compact(&mut self) {
+        let old2new = self.compact_states();
+        self.initial = self.initial.iter().map(|s| old2new[s]).collect();
+    }
+
+    pub fn add_transition(&mut self, from: StateId, symbol: A, to: StateId) {
+        self.try_add_transition(from, symbol, to)
+            .unwrap_or_else(|e| panic!("{}", e));
+    }
+
+    pub fn try_add_transition(
+        &mut self,
+        from: StateId,
+        symbol: A,
+        to: StateId,
+    ) -> Result<(), Error> {
+        self.check_state(from)?;
+        self.check_state(to)?;
+        self.state_mut(from).add_transition(symbol, to);
+        Ok(())
+    }
+
+    pub fn add_epsilon_transition(&mut self, from: StateId, to: StateId) {
+        self.try_add_epsilon_transition(from, to)
+            .unwrap_or_else(|e| panic!("{}", e));
+    }
+
+    pub fn try_add_epsilon_transition(&mut self, from: StateId, to: StateId) -> Result<(), Error> {
+        self.check_state(from)?;
+        self.check_state(to)?;
+        self.state_mut(from).add_epsilon_transition(to);
+        Ok(())
     }
 
     pub fn num_transitions(&self) -> usize {
@@ -61,10 +206,6 @@ impl<A: Alphabet> Nfa<A> {
         self.state(state).accepting
     }
 
-    pub fn states(&self) -> impl Iterator<Item = &State<A>> {
-        self.states.iter()
-    }
-
     pub fn transitions(&self) -> impl Iterator<Item = (&State<A>, A, &State<A>)> + '_ {
         self.states().flat_map(move |state| {
             state
@@ -83,35 +224,73 @@ impl<A: Alphabet> Nfa<A> {
     }
 }
 
-impl<A: Alphabet> Default for Nfa<A> {
-    fn default() -> Self {
-        Self::new()
+impl<A: Alphabet> Automaton for Nfa<A> {
+    type StateId = StateId;
+    type Input = A;
+
+    fn num_states(&self) -> usize {
+        self.num_states()
+    }
+
+    fn state_ids(&self) -> impl Iterator<Item = StateId> + '_ {
+        self.states().map(|state| state.id)
+    }
+
+    fn initial_state_ids(&self) -> impl Iterator<Item = StateId> + '_ {
+        self.initial.iter().copied()
+    }
+
+    fn successors(&self, state: StateId) -> impl Iterator<Item = (Option<A>, StateId)> + '_ {
+        let state = self.state(state);
+        state
+            .transitions()
+            .map(|(symbol, to)| (Some(symbol), to))
+            .chain(state.next_epsilon().iter().map(|&to| (None, to)))
     }
 }
 
-impl<A: Alphabet> Index<StateId> for Nfa<A> {
-    type Output = State<A>;
+impl<A: Alphabet> Acceptor for Nfa<A> {
+    fn is_accepting(&self, state: StateId) -> bool {
+        self.accepting(state)
+    }
 
-    fn index(&self, index: StateId) -> &Self::Output {
-        self.state(index)
+    fn accepts(&self, word: impl IntoIterator<Item = A>) -> bool {
+        self.accepts(word)
     }
 }
 
-impl<A: Alphabet> IndexMut<StateId> for Nfa<A> {
-    fn index_mut(&mut self, index: StateId) -> &mut Self::Output {
-        self.state_mut(index)
+impl<A: Alphabet> Build for Nfa<A> {
+    fn add_state_like(&mut self, source: &Self, state: StateId) -> StateId {
+        self.add_state(source.accepting(state))
+    }
+
+    fn add_successor(&mut self, from: StateId, input: Option<A>, to: StateId) {
+        match input {
+            Some(symbol) => self.add_transition(from, symbol, to),
+            None => self.add_epsilon_transition(from, to),
+        }
+    }
+
+    fn set_initial_state_ids(&mut self, states: impl IntoIterator<Item = StateId>) {
+        self.set_initial_states(states);
     }
 }
 
 impl<A: Alphabet> Nfa<A> {
-    pub fn next(&self, state: StateId, symbol: A) -> Option<&Vec<StateId>> {
+    pub fn next(&self, state: StateId, symbol: A) -> Option<&BTreeSet<StateId>> {
         self.state(state).next(symbol)
     }
 
-    pub fn next_epsilon(&self, state: StateId) -> &HashSet<StateId> {
+    pub fn next_epsilon(&self, state: StateId) -> &BTreeSet<StateId> {
         self.state(state).next_epsilon()
     }
 
+    /// ε-closure of the initial states.
+    fn initial_closure(&self) -> BTreeSet<StateId> {
+        self.multi_epsilon_closure(self.initial.iter().copied().collect())
+            .collect()
+    }
+
     fn epsilon_closure(&self, start: StateId) -> impl Iterator<Item = StateId> + '_ {
         self.multi_epsilon_closure(vec![start])
     }
@@ -120,72 +299,115 @@ impl<A: Alphabet> Nfa<A> {
         multi_dfs(start, |state| self.next_epsilon(state).iter().copied())
     }
 
+    /// Successors of a set of states on `symbol`, closed under ε-transitions.
+    fn post(&self, states: &BTreeSet<StateId>, symbol: A) -> BTreeSet<StateId> {
+        let mut next = BTreeSet::new();
+        for &state in states {
+            if let Some(next_states) = self.next(state, symbol) {
+                next.extend(self.multi_epsilon_closure(next_states.iter().copied().collect()));
+            }
+        }
+        next
+    }
+
     fn any_accepting(&self, states: impl IntoIterator<Item = StateId>) -> bool {
         states.into_iter().any(|s| self.accepting(s))
     }
 
+    /// Runs the NFA on `word`, in its [`BitNfa`] form unless it has more
+    /// than a few thousand states, as for [`Nfa::to_dfa`].
     pub fn accepts(&self, word: impl IntoIterator<Item = A>) -> bool {
-        if self.states.is_empty() {
-            return false;
+        if self.states.id_bound() <= BIT_NFA_MAX_STATES {
+            return self.to_bit_nfa().accepts(word);
         }
+        self.accepts_by_sets(word)
+    }
 
-        let mut current = self.epsilon_closure(0).collect::<BTreeSet<_>>();
+    /// Runs the NFA on `word` on sets of state ids.
+    fn accepts_by_sets(&self, word: impl IntoIterator<Item = A>) -> bool {
+        let mut current = self.initial_closure();
 
         for symbol in word {
-            let mut next = BTreeSet::new();
-            for state in current {
-                if let Some(next_states) = self.next(state, symbol) {
-                    next.extend(self.multi_epsilon_closure(next_states.clone()))
-                }
-            }
-            current = next;
+            current = self.post(&current, symbol);
         }
 
         self.any_accepting(current)
     }
 
+    /// Determinizes the NFA over `alphabet` with the subset construction.
+    ///
+    /// NFAs of up to a few thousand states are determinized in their
+    /// [`BitNfa`] form, larger ones on sets of state ids, as their successor
+    /// masks would take too much memory.
     pub fn to_dfa(&self, alphabet: &[A]) -> Dfa<A> {
-        let mut dfa = Dfa::new();
-        let mut state_map = HashMap::new();
-        let mut queue = Vec::new();
-
-        let initial_nfa_state = self.epsilon_closure(0).collect::<BTreeSet<_>>();
-        let initial_accepting = self.any_accepting(initial_nfa_state.iter().copied());
-        let initial_dfa_state = dfa.add_state(initial_accepting);
-        state_map.insert(initial_nfa_state.clone(), initial_dfa_state);
-        queue.push(initial_nfa_state);
-
-        while let Some(current_nfa_state) = queue.pop() {
-            let current_state = state_map[&current_nfa_state];
-
-            for &symbol in alphabet {
-                let mut next_nfa_state = BTreeSet::new();
-                for &nfa_state in &current_nfa_state {
-                    if let Some(next) = self.next(nfa_state, symbol) {
-                        next_nfa_state.extend(self.multi_epsilon_closure(next.clone()));
-                    }
-                }
-
-                if !next_nfa_state.is_empty() {
-                    let next_dfa_state =
-                        *state_map.entry(next_nfa_state.clone()).or_insert_with(|| {
-                            let accepting = self.any_accepting(next_nfa_state.iter().copied());
-                            let new_dfa_state = dfa.add_state(accepting);
-                            queue.push(next_nfa_state);
-                            new_dfa_state
-                        });
-                    dfa.add_transition(current_state, symbol, next_dfa_state);
-                }
-            }
+        if self.states.id_bound() <= BIT_NFA_MAX_STATES {
+            return self.to_bit_nfa().to_dfa(alphabet);
         }
-
+        let (dfa, _) = self
+            .subset_construction(alphabet, None)
+            .expect("no state limit");
         dfa
     }
+
+    /// Subset construction over `alphabet` on sets of state ids, see
+    /// [`Nfa::to_dfa_with_map`].
+    fn subset_construction(
+        &self,
+        alphabet: &[A],
+        limit: Option<usize>,
+    ) -> Result<(Dfa<A>, Vec<BTreeSet<StateId>>), Error> {
+        subset::subset_construction(self, alphabet, limit)
+    }
+
+    /// Returns the symbols the NFA has transitions on, in increasing order.
+    fn symbols(&self) -> Vec<A> {
+        let mut symbols: Vec<A> = self.transitions().map(|(_, symbol, _)| symbol).collect();
+        symbols.sort_unstable();
+        symbols.dedup();
+        symbols
+    }
+
+    /// Determinizes the NFA over the symbols it has transitions on.
+    pub fn determinize(&self) -> Dfa<A> {
+        if self.states.id_bound() > BIT_NFA_MAX_STATES {
+            return self.to_dfa(&self.symbols());
+        }
+        let bit = self.to_bit_nfa();
+        bit.to_dfa(bit.alphabet())
+    }
+
+    /// Determinizes the NFA like [`Nfa::to_dfa`], also returning for each DFA
+    /// state, indexed by its id, the set of NFA states it represents.
+    ///
+    /// Without an `alphabet`, the symbols the NFA has transitions on are
+    /// used, as by [`Nfa::determinize`]. Fails with
+    /// [`Error::StateLimitExceeded`] instead of building a DFA with more than
+    /// `limit` states.
+    pub fn to_dfa_with_map(
+        &self,
+        alphabet: Option<&[A]>,
+        limit: Option<usize>,
+    ) -> Result<(Dfa<A>, Vec<BTreeSet<StateId>>), Error> {
+        if self.states.id_bound() > BIT_NFA_MAX_STATES {
+            return match alphabet {
+                Some(alphabet) => self.subset_construction(alphabet, limit),
+                None => self.subset_construction(&self.symbols(), limit),
+            };
+        }
+        let bit = self.to_bit_nfa();
+        let alphabet = alphabet.unwrap_or(bit.alphabet());
+        let (dfa, subsets) = bit.subset_construction(alphabet, limit)?;
+        let subsets = subsets
+            .iter()
+            .map(|subset| subset.iter().map(StateId::new).collect())
+            .collect();
+        Ok((dfa, subsets))
+    }
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::test_common::generate_words;
+    use crate::test_common::{generate_words, nth_from_end};
 
     use super::*;
 
@@ -293,4 +515,128 @@ mod tests {
             assert_eq!(dfa.accepts(word.clone()), nfa.accepts(word));
         }
     }
+
+    #[test]
+    fn test_determinize_with_map() {
+        let nfa = nth_from_end(2);
+        let (q0, q2) = (StateId::new(0), StateId::new(2));
+
+        let dfa = nfa.determinize();
+        assert_eq!(dfa.num_states(), 4);
+        for word in generate_words(&['a', 'b', 'c'], 6) {
+            assert_eq!(dfa.accepts(word.clone()), nfa.accepts(word));
+        }
+
+        let (dfa, subsets) = nfa.to_dfa_with_map(None, None).unwrap();
+        assert_eq!(subsets.len(), dfa.num_states());
+        assert_eq!(subsets[0], BTreeSet::from([q0]));
+        for state in dfa.states() {
+            let subset = &subsets[state.id.index()];
+            assert_eq!(state.accepting, subset.contains(&q2));
+            for (symbol, to) in state.transitions() {
+                let expected: BTreeSet<StateId> = subset
+                    .iter()
+                    .filter_map(|&s| nfa.next(s, symbol))
+                    .flatten()
+                    .copied()
+                    .collect();
+                assert_eq!(subsets[to.index()], expected);
+            }
+        }
+
+        // Symbols of an explicit alphabet the NFA never reads lead nowhere
+        let (dfa, _) = nfa.to_dfa_with_map(Some(&['a', 'b', 'c']), None).unwrap();
+        assert_eq!(dfa.num_states(), 4);
+        assert!(dfa.next(dfa.initial().unwrap(), 'c').is_none());
+
+        assert!(nfa.to_dfa_with_map(None, Some(4)).is_ok());
+        assert_eq!(
+            nfa.to_dfa_with_map(Some(&['a', 'b']), Some(3)).unwrap_err(),
+            Error::StateLimitExceeded(3)
+        );
+    }
+
+    #[test]
+    fn test_initial_states() {
+        let mut nfa = Nfa::new();
+        let a = nfa.add_state(false);
+        let b = nfa.add_state(false);
+        let c = nfa.add_state(true);
+        nfa.add_transition(a, 'x', c);
+        nfa.add_transition(b, 'y', c);
+        assert_eq!(nfa.initial_states(), &BTreeSet::from([a]));
+        assert!(!nfa.accepts(['y']));
+
+        nfa.add_initial(b);
+        assert!(nfa.accepts(['x']));
+        assert!(nfa.accepts(['y']));
+        let dfa = nfa.to_dfa(&['x', 'y']);
+        for word in generate_words(&['x', 'y'], 3) {
+            assert_eq!(dfa.accepts(word.clone()), nfa.accepts(word));
+        }
+
+        nfa.set_initial(c);
+        assert!(nfa.accepts([]));
+        assert!(!nfa.accepts(['x']));
+        assert_eq!(
+            nfa.try_set_initial_states([a, StateId::new(9)]),
+            Err(Error::UnknownState(9))
+        );
+        assert_eq!(nfa.initial_states(), &BTreeSet::from([c]));
+        nfa.set_initial_states([]);
+        assert!(!nfa.accepts([]));
+        assert_eq!(nfa.shortest_word(), None);
+    }
+
+    #[test]
+    fn test_remove_and_merge_states() {
+        let mut nfa = Nfa::new();
+        // Words ending with 'b', with two redundant copies of the loop
+        let a = nfa.add_state(false);
+        let b = nfa.add_state(false);
+        let c = nfa.add_state(true);
+        for s in [a, b] {
+            nfa.add_transition(s, 'a', s);
+            nfa.add_transition(s, 'b', s);
+            nfa.add_transition(s, 'b', c);
+        }
+        nfa.add_epsilon_transition(a, b);
+        let dfa = nfa.to_dfa(&['a', 'b']);
+
+        nfa.merge_states(a, b);
+        assert_eq!(nfa.num_states(), 2);
+        assert_eq!(nfa.num_transitions(), 3);
+        assert_eq!(nfa.num_epsilon_transitions(), 0);
+        for word in generate_words(&['a', 'b'], 6) {
+            assert_eq!(nfa.accepts(word.clone()), dfa.accepts(word));
+        }
+
+        assert!(nfa.remove_transition(a, 'a', a));
+        assert!(!nfa.remove_transition(a, 'a', a));
+        assert!(!nfa.accepts(['a', 'b']));
+        nfa.add_epsilon_transition(a, c);
+        assert!(nfa.accepts([]));
+        assert!(nfa.remove_epsilon_transition(a, c));
+        assert_eq!(
+            nfa.try_add_epsilon_transition(a, StateId::new(9)),
+            Err(Error::UnknownState(9))
+        );
+        assert_eq!(
+            nfa.try_add_initial(StateId::new(9)),
+            Err(Error::UnknownState(9))
+        );
+        assert_eq!(
+            nfa.try_state(StateId::new(9)).map(|state| state.id),
+            Err(Error::UnknownState(9))
+        );
+        assert_eq!(nfa.try_state_mut(a).map(|state| state.id), Ok(a));
+        assert!(!nfa.accepts([]));
+
+        nfa.remove_state(c);
+        assert_eq!(nfa.num_transitions(), 1);
+        nfa.remove_state(a);
+        assert!(nfa.initial_states().is_empty());
+        nfa.compact();
+        assert_eq!(nfa.num_states(), 0);
+    }
 }
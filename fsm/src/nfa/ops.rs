@@ -0,0 +1,176 @@
+use std::collections::HashMap;
+
+use crate::alphabet::Alphabet;
+use crate::nfa::state::StateId;
+use crate::nfa::Nfa;
+
+impl<A: Alphabet> Nfa<A> {
+    /// Returns an NFA accepting the words `uv` with `u` accepted by `self`
+    /// and `v` accepted by `other`.
+    pub fn concat(self, other: Nfa<A>) -> Nfa<A> {
+        if self.initial.is_empty() || other.initial.is_empty() {
+            return Nfa::new();
+        }
+        let mut nfa = self;
+        let accepting = nfa.accepting_states();
+        let old2new = nfa.embed(&other);
+        let initial: Vec<StateId> = other.initial.iter().map(|s| old2new[s]).collect();
+        for state in accepting {
+            nfa.state_mut(state).accepting = false;
+            for &to in &initial {
+                nfa.add_epsilon_transition(state, to);
+            }
+        }
+        nfa
+    }
+
+    /// Returns an NFA accepting the words accepted by `self` or by `other`.
+    pub fn union(self, other: Nfa<A>) -> Nfa<A> {
+        let mut nfa = Nfa::new();
+        let start = nfa.add_state(false);
+        for operand in [self, other] {
+            let old2new = nfa.embed(&operand);
+            for initial in &operand.initial {
+                nfa.add_epsilon_transition(start, old2new[initial]);
+            }
+        }
+        nfa
+    }
+
+    /// Returns an NFA accepting the concatenations of zero or more words
+    /// accepted by `self` (Kleene star).
+    pub fn star(self) -> Nfa<A> {
+        let mut nfa = Nfa::new();
+        let start = nfa.add_state(true);
+        let old2new = nfa.embed(&self);
+        for initial in &self.initial {
+            nfa.add_epsilon_transition(start, old2new[initial]);
+        }
+        for state in self.accepting_states() {
+            nfa.add_epsilon_transition(old2new[&state], start);
+        }
+        nfa
+    }
+
+    /// Returns an NFA accepting the concatenations of one or more words
+    /// accepted by `self`.
+    pub fn plus(self) -> Nfa<A> {
+        let mut nfa = self;
+        let initial = nfa.initial.clone();
+        for state in nfa.accepting_states() {
+            for &to in &initial {
+                nfa.add_epsilon_transition(state, to);
+            }
+        }
+        nfa
+    }
+
+    /// Returns an NFA accepting the empty word and the words accepted by `self`.
+    pub fn optional(self) -> Nfa<A> {
+        let mut nfa = Nfa::new();
+        let start = nfa.add_state(true);
+        let old2new = nfa.embed(&self);
+        for initial in &self.initial {
+            nfa.add_epsilon_transition(start, old2new[initial]);
+        }
+        nfa
+    }
+
+    fn accepting_states(&self) -> Vec<StateId> {
+        self.states()
+            .filter(|state| state.accepting)
+            .map(|state| state.id)
+            .collect()
+    }
+
+    /// Copies the states and transitions of `other` into `self` under fresh
+    /// ids, and returns the mapping from old to new ids. `self` must not be
+    /// empty, so that its initial states are left unchanged.
+    fn embed(&mut self, other: &Nfa<A>) -> HashMap<StateId, StateId> {
+        let old2new: HashMap<_, _> = other
+            .states()
+            .map(|state| (state.id, self.add_state(state.accepting)))
+            .collect();
+        for state in other.states() {
+            let new_from = old2new[&state.id];
+            for (symbol, to) in state.transitions() {
+                self.add_transition(new_from, symbol, old2new[&to]);
+            }
+            for to in state.next_epsilon() {
+                self.add_epsilon_transition(new_from, old2new[to]);
+            }
+        }
+        old2new
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::test_common::{generate_words, word};
+
+    use super::*;
+
+    #[test]
+    fn test_regular_operations() {
+        // (ab|b)*a?
+        let nfa = word(['a'])
+            .concat(word(['b']))
+            .union(word(['b']))
+            .star()
+            .concat(word(['a']).optional());
+
+        let expected = |word: &[char]| {
+            let body = word.strip_suffix(&['a']).unwrap_or(word);
+            let mut rest = body;
+            loop {
+                if rest.is_empty() {
+                    return true;
+                } else if let Some(r) = rest.strip_prefix(&['a', 'b']) {
+                    rest = r;
+                } else if let Some(r) = rest.strip_prefix(&['b']) {
+                    rest = r;
+                } else {
+                    return false;
+                }
+            }
+        };
+        for word in generate_words(&['a', 'b'], 8) {
+            assert_eq!(nfa.accepts(word.clone()), expected(&word), "{:?}", word);
+        }
+    }
+
+    #[test]
+    fn test_plus() {
+        #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Ord, PartialOrd)]
+        enum Sigma {
+            Zero,
+            One,
+        }
+        use Sigma::*;
+
+        // (01)+
+        let nfa = word([Zero]).concat(word([One])).plus();
+        assert!(!nfa.accepts(vec![]));
+        assert!(nfa.accepts(vec![Zero, One]));
+        assert!(nfa.accepts(vec![Zero, One, Zero, One]));
+        assert!(!nfa.accepts(vec![Zero, One, Zero]));
+        assert!(!nfa.accepts(vec![One, Zero]));
+    }
+
+    #[test]
+    fn test_empty_operands() {
+        let empty = Nfa::<char>::new;
+        assert_eq!(word(['a']).concat(empty()).num_states(), 0);
+        assert_eq!(empty().concat(word(['a'])).num_states(), 0);
+
+        let union = empty().union(word(['a']));
+        assert!(union.accepts(['a']));
+        assert!(!union.accepts([]));
+
+        let star = empty().star();
+        assert!(star.accepts([]));
+        assert!(!star.accepts(['a']));
+        assert!(empty().optional().accepts([]));
+        assert!(!empty().plus().accepts([]));
+    }
+}
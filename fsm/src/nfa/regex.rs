@@ -0,0 +1,77 @@
+use std::collections::HashMap;
+use std::fmt::Display;
+
+use crate::alphabet::Alphabet;
+use crate::nfa::Nfa;
+use crate::util::regex::state_elimination;
+
+impl<A: Alphabet + Display> Nfa<A> {
+    /// Returns a regular expression describing the language of the NFA, or
+    /// `None` if the language is empty.
+    ///
+    /// ε-transitions are eliminated along with the states; see
+    /// [`Dfa::to_regex`](crate::dfa::Dfa::to_regex) for the output syntax.
+    pub fn to_regex(&self) -> Option<String> {
+        let index: HashMap<_, _> = self.states().enumerate().map(|(i, s)| (s.id, i)).collect();
+        let accepting: Vec<bool> = self.states().map(|state| state.accepting).collect();
+        let edges = self
+            .transitions()
+            .map(|(from, symbol, to)| (index[&from.id], Some(symbol), index[&to.id]))
+            .chain(
+                self.epsilon_transitions()
+                    .map(|(from, to)| (index[&from.id], None, index[&to.id])),
+            );
+        state_elimination(
+            self.num_states(),
+            self.initial.iter().map(|s| index[s]),
+            |s| accepting[s],
+            edges,
+        )
+        .map(|regex| regex.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use regex_thompson::Regex;
+
+    use crate::test_common::generate_strings;
+
+    use super::*;
+
+    #[test]
+    fn test_to_regex() {
+        let mut nfa = Nfa::new();
+        // Words ending with '1', with a redundant ε-loop
+        let a = nfa.add_state(false);
+        let b = nfa.add_state(true);
+        nfa.add_epsilon_transition(a, a);
+        nfa.add_transition(a, '0', a);
+        nfa.add_transition(a, '1', a);
+        nfa.add_transition(a, '1', b);
+        nfa.add_transition(b, '0', a);
+        nfa.add_transition(b, '1', b);
+
+        let pattern = nfa.to_regex().unwrap();
+        let re = Regex::new(&pattern);
+        for word in generate_strings(&['0', '1'], 8) {
+            assert_eq!(nfa.accepts(word.chars()), re.matches(&word), "{}", pattern);
+        }
+    }
+
+    #[test]
+    fn test_to_regex_epsilon() {
+        let mut nfa = Nfa::new();
+        let a = nfa.add_state(false);
+        let b = nfa.add_state(false);
+        let c = nfa.add_state(true);
+        nfa.add_epsilon_transition(a, b);
+        nfa.add_transition(b, 'x', b);
+        nfa.add_epsilon_transition(b, c);
+        nfa.add_transition(a, 'y', c);
+        assert_eq!(nfa.to_regex().unwrap(), "y|x*");
+
+        let empty = Nfa::<char>::new();
+        assert_eq!(empty.to_regex(), None);
+    }
+}
@@ -1,21 +1,25 @@
-use std::collections::HashMap;
+use std::collections::BTreeSet;
 
+use serde::de::Error as _;
 use serde::{Deserialize, Deserializer, Serialize, Serializer};
 
 use crate::alphabet::Alphabet;
 use crate::nfa::Nfa;
+use crate::util::serde::Renumbering;
 
-use super::State;
+use super::{State, StateId};
 
 impl<A: Alphabet + Serialize> Serialize for Nfa<A> {
     fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
         #[derive(Serialize)]
         #[serde(rename = "Nfa")]
         struct NfaHelper<'a, A: Alphabet> {
+            initial: &'a BTreeSet<StateId>,
             states: Vec<&'a State<A>>,
         }
 
         let helper = NfaHelper {
+            initial: &self.initial,
             states: self.states().collect(),
         };
         helper.serialize(serializer)
@@ -30,25 +34,38 @@ impl<'de, A: Alphabet + Deserialize<'de>> Deserialize<'de> for Nfa<A> {
         #[derive(Deserialize)]
         #[serde(rename = "Nfa")]
         struct NfaHelper<A: Alphabet> {
+            /// Defaults to the first listed state.
+            #[serde(default)]
+            initial: Option<BTreeSet<StateId>>,
             states: Vec<State<A>>,
         }
 
         let helper = NfaHelper::deserialize(deserializer)?;
         let mut nfa = Nfa::new();
-        let old2new: HashMap<_, _> = helper
-            .states
-            .iter()
-            .map(|state| (state.id, nfa.add_state(state.accepting)))
-            .collect();
+        let ids = Renumbering::new(
+            helper
+                .states
+                .iter()
+                .map(|state| (state.id, nfa.add_state(state.accepting))),
+        )
+        .map_err(D::Error::custom)?;
+        let new_id = |old: StateId| ids.get(old).map_err(D::Error::custom);
         for old_from_state in &helper.states {
-            let new_from = old2new[&old_from_state.id];
+            let new_from = new_id(old_from_state.id)?;
             for (symbol, old_to) in old_from_state.transitions() {
-                nfa.add_transition(new_from, symbol, old2new[&old_to]);
+                nfa.add_transition(new_from, symbol, new_id(old_to)?);
             }
             for &old_to in old_from_state.next_epsilon() {
-                nfa.add_epsilon_transition(new_from, old2new[&old_to]);
+                nfa.add_epsilon_transition(new_from, new_id(old_to)?);
             }
         }
+        if let Some(old_initial) = helper.initial {
+            let initial = old_initial
+                .into_iter()
+                .map(new_id)
+                .collect::<Result<Vec<_>, _>>()?;
+            nfa.set_initial_states(initial);
+        }
         Ok(nfa)
     }
 }
@@ -78,4 +95,67 @@ mod tests {
             assert_eq!(nfa.accepts(word.chars()), nfa2.accepts(word.chars()));
         }
     }
+
+    #[test]
+    fn test_nfa_serde_initial() {
+        let mut nfa = Nfa::new();
+        let a = nfa.add_state(false);
+        let b = nfa.add_state(false);
+        let c = nfa.add_state(true);
+        nfa.add_transition(a, 'x', c);
+        nfa.add_transition(b, 'y', c);
+        nfa.set_initial_states([a, b]);
+
+        let json = serde_json::to_string(&nfa).unwrap();
+        let nfa2 = decltype(&nfa, serde_json::from_str(&json).unwrap());
+        assert_eq!(nfa2.initial_states(), &BTreeSet::from([a, b]));
+        assert!(nfa2.accepts("x".chars()));
+        assert!(nfa2.accepts("y".chars()));
+
+        nfa.set_initial_states([]);
+        let json = serde_json::to_string(&nfa).unwrap();
+        let nfa3 = decltype(&nfa, serde_json::from_str(&json).unwrap());
+        assert!(nfa3.initial_states().is_empty());
+        assert!(!nfa3.accepts("x".chars()));
+    }
+
+    #[test]
+    fn test_nfa_serde_errors() {
+        let dangling = r#"{"states":[
+            {"id":0,"accepting":true,"transitions":{},"epsilon_transitions":[2]}
+        ]}"#;
+        let error = serde_json::from_str::<Nfa<char>>(dangling).unwrap_err();
+        assert!(
+            error.to_string().contains("unknown state id 2"),
+            "{}",
+            error
+        );
+
+        let bad_initial = r#"{"initial":[0,3],"states":[
+            {"id":0,"accepting":true,"transitions":{},"epsilon_transitions":[]}
+        ]}"#;
+        let error = serde_json::from_str::<Nfa<char>>(bad_initial).unwrap_err();
+        assert!(
+            error.to_string().contains("unknown state id 3"),
+            "{}",
+            error
+        );
+    }
+
+    #[test]
+    fn test_nfa_serde_sorted() {
+        let mut nfa = Nfa::new();
+        let a = nfa.add_state(false);
+        let b = nfa.add_state(true);
+        nfa.add_transition(a, 'b', b);
+        nfa.add_transition(a, 'a', b);
+        nfa.add_transition(a, 'a', a);
+        nfa.add_epsilon_transition(b, a);
+
+        let json = serde_json::to_string(&nfa).unwrap();
+        assert_eq!(
+            json,
+            r#"{"initial":[0],"states":[{"id":0,"accepting":false,"transitions":{"a":[0,1],"b":[1]},"epsilon_transitions":[]},{"id":1,"accepting":true,"transitions":{},"epsilon_transitions":[0]}]}"#
+        );
+    }
 }
@@ -0,0 +1,270 @@
+use std::collections::BTreeSet;
+
+use crate::alphabet::Alphabet;
+use crate::nfa::state::StateId;
+use crate::nfa::Nfa;
+
+/// Preorder on the states of an NFA, as a matrix indexed by state id.
+struct Relation(Vec<Vec<bool>>);
+
+impl Relation {
+    /// Greatest relation included in `base` such that whenever `p` is related
+    /// to `q`, every edge `p -a-> p'` is matched by an edge `q -a-> q'` with
+    /// `p'` related to `q'`. `edges` is indexed by state id.
+    fn greatest<A: Alphabet>(
+        ids: &[StateId],
+        edges: &[Vec<(A, StateId)>],
+        base: impl Fn(StateId, StateId) -> bool,
+    ) -> Relation {
+        let mut related = vec![vec![false; edges.len()]; edges.len()];
+        for &p in ids {
+            for &q in ids {
+                related[p.index()][q.index()] = base(p, q);
+            }
+        }
+        let mut changed = true;
+        while changed {
+            changed = false;
+            for &p in ids {
+                for &q in ids {
+                    if !related[p.index()][q.index()] {
+                        continue;
+                    }
+                    let matched = edges[p.index()].iter().all(|&(symbol, p_next)| {
+                        edges[q.index()].iter().any(|&(other, q_next)| {
+                            other == symbol && related[p_next.index()][q_next.index()]
+                        })
+                    });
+                    if !matched {
+                        related[p.index()][q.index()] = false;
+                        changed = true;
+                    }
+                }
+            }
+        }
+        Relation(related)
+    }
+
+    fn holds(&self, p: StateId, q: StateId) -> bool {
+        self.0[p.index()][q.index()]
+    }
+
+    fn holds_strictly(&self, p: StateId, q: StateId) -> bool {
+        self.holds(p, q) && !self.holds(q, p)
+    }
+}
+
+impl<A: Alphabet> Nfa<A> {
+    /// Returns the maximal forward simulation, as the pairs `(p, q)` such
+    /// that `q` simulates `p`: `q` is accepting if `p` is, and every
+    /// transition `p -a-> p'` is matched by a transition `q -a-> q'` with `q'`
+    /// simulating `p'`. The relation is a preorder, and `q` simulating `p`
+    /// implies that every word accepted from `p` is accepted from `q`.
+    ///
+    /// It is computed on the ε-free equivalent given by
+    /// [`Nfa::remove_epsilons`], which has the same state ids.
+    pub fn simulation_relation(&self) -> BTreeSet<(StateId, StateId)> {
+        let nfa = self.remove_epsilons();
+        let relation = nfa.forward_simulation();
+        let ids: Vec<StateId> = nfa.states().map(|state| state.id).collect();
+        ids.iter()
+            .flat_map(|&p| ids.iter().map(move |&q| (p, q)))
+            .filter(|&(p, q)| relation.holds(p, q))
+            .collect()
+    }
+
+    /// Returns an equivalent NFA without ε-transitions, shrunk using
+    /// simulation relations.
+    ///
+    /// States that simulate each other forward (same future) or backward
+    /// (same past) are merged, then transitions to a state strictly simulated
+    /// by another successor on the same symbol are pruned, and likewise for
+    /// initial states. Finally, the result is [trimmed](Nfa::trim).
+    pub fn reduce(&self) -> Nfa<A> {
+        let mut nfa = self.remove_epsilons().trim();
+        let forward = nfa.forward_simulation();
+        nfa.merge_equivalent(&forward);
+        let backward = nfa.backward_simulation();
+        nfa.merge_equivalent(&backward);
+
+        let forward = nfa.forward_simulation();
+        let subsumed = |targets: &BTreeSet<StateId>, to: StateId| {
+            targets
+                .iter()
+                .any(|&other| forward.holds_strictly(to, other))
+        };
+        let initial: Vec<StateId> = nfa
+            .initial
+            .iter()
+            .copied()
+            .filter(|&s| !subsumed(&nfa.initial, s))
+            .collect();
+        let pruned: Vec<(StateId, A, StateId)> = nfa
+            .states()
+            .flat_map(|state| {
+                state
+                    .transitions()
+                    .filter(|&(symbol, to)| subsumed(state.next(symbol).unwrap(), to))
+                    .map(move |(symbol, to)| (state.id, symbol, to))
+            })
+            .collect();
+        nfa.set_initial_states(initial);
+        for (from, symbol, to) in pruned {
+            nfa.remove_transition(from, symbol, to);
+        }
+        nfa.trim()
+    }
+
+    /// Maximal forward simulation of an ε-free NFA.
+    fn forward_simulation(&self) -> Relation {
+        let mut edges = vec![Vec::new(); self.states.id_bound()];
+        for state in self.states() {
+            edges[state.id.index()] = state.transitions().collect();
+        }
+        let ids: Vec<StateId> = self.states().map(|state| state.id).collect();
+        Relation::greatest(&ids, &edges, |p, q| !self.accepting(p) || self.accepting(q))
+    }
+
+    /// Maximal backward simulation of an ε-free NFA: the forward simulation
+    /// of its reverse, with initial states playing the part of accepting
+    /// ones.
+    fn backward_simulation(&self) -> Relation {
+        let mut edges = vec![Vec::new(); self.states.id_bound()];
+        for (from, symbol, to) in self.transitions() {
+            edges[to.id.index()].push((symbol, from.id));
+        }
+        let ids: Vec<StateId> = self.states().map(|state| state.id).collect();
+        Relation::greatest(&ids, &edges, |p, q| {
+            !self.initial.contains(&p) || self.initial.contains(&q)
+        })
+    }
+
+    /// Merges every class of states related both ways into its smallest
+    /// member.
+    fn merge_equivalent(&mut self, relation: &Relation) {
+        let ids: Vec<StateId> = self.states().map(|state| state.id).collect();
+        let mut merged = BTreeSet::new();
+        for (i, &p) in ids.iter().enumerate() {
+            if merged.contains(&p) {
+                continue;
+            }
+            for &q in &ids[i + 1..] {
+                if !merged.contains(&q) && relation.holds(p, q) && relation.holds(q, p) {
+                    self.merge_states(p, q);
+                    merged.insert(q);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::test_common::{generate_words, nth_from_end, word};
+
+    use super::*;
+
+    fn assert_reduced(nfa: &Nfa<char>, symbols: &[char], max_length: usize) -> Nfa<char> {
+        let reduced = nfa.reduce();
+        assert_eq!(reduced.num_epsilon_transitions(), 0);
+        assert!(reduced.num_states() <= nfa.num_states());
+        for word in generate_words(symbols, max_length) {
+            assert_eq!(
+                reduced.accepts(word.clone()),
+                nfa.accepts(word.clone()),
+                "{:?}",
+                word
+            );
+        }
+        reduced
+    }
+
+    #[test]
+    fn test_simulation_relation() {
+        let mut nfa = Nfa::new();
+        let a = nfa.add_state(false);
+        let b = nfa.add_state(true);
+        let c = nfa.add_state(true);
+        let d = nfa.add_state(true);
+        nfa.add_transition(a, 'x', b);
+        nfa.add_transition(a, 'x', c);
+        nfa.add_transition(c, 'y', d);
+
+        let relation = nfa.simulation_relation();
+        // Reflexive
+        for s in [a, b, c, d] {
+            assert!(relation.contains(&(s, s)));
+        }
+        assert!(relation.contains(&(b, c)));
+        assert!(!relation.contains(&(c, b)));
+        assert!(relation.contains(&(b, d)) && relation.contains(&(d, b)));
+        assert!(relation.contains(&(d, c)));
+        // Accepting states are only simulated by accepting ones
+        assert!(!relation.contains(&(b, a)));
+        assert!(!relation.contains(&(a, b)));
+    }
+
+    #[test]
+    fn test_simulation_relation_epsilon() {
+        let mut nfa = Nfa::new();
+        let a = nfa.add_state(false);
+        let b = nfa.add_state(false);
+        let c = nfa.add_state(true);
+        nfa.add_epsilon_transition(a, b);
+        nfa.add_transition(b, 'x', c);
+
+        let relation = nfa.simulation_relation();
+        assert!(relation.contains(&(a, b)) && relation.contains(&(b, a)));
+        assert!(!relation.contains(&(c, a)));
+    }
+
+    #[test]
+    fn test_reduce_merges_duplicates() {
+        // (ab|ab|ac)*, built with ε-transitions and duplicate branches
+        let nfa = word("ab".chars())
+            .union(word("ab".chars()))
+            .union(word("ac".chars()))
+            .star();
+        let reduced = assert_reduced(&nfa, &['a', 'b', 'c'], 6);
+        // Back at the start after "ab" or "ac", with one state in between
+        assert_eq!(reduced.num_states(), 2);
+    }
+
+    #[test]
+    fn test_reduce_prunes_subsumed_transitions() {
+        let mut nfa = Nfa::new();
+        // From `a`, 'x' leads either to `b`, accepting "y", or to `c`,
+        // accepting "y" and "z": the transition to `b` is redundant.
+        let a = nfa.add_state(false);
+        let b = nfa.add_state(false);
+        let c = nfa.add_state(false);
+        let d = nfa.add_state(true);
+        nfa.add_transition(a, 'x', b);
+        nfa.add_transition(a, 'x', c);
+        nfa.add_transition(b, 'y', d);
+        nfa.add_transition(c, 'y', d);
+        nfa.add_transition(c, 'z', d);
+
+        let reduced = assert_reduced(&nfa, &['x', 'y', 'z'], 4);
+        assert_eq!(reduced.num_states(), 3);
+        assert_eq!(reduced.num_transitions(), 3);
+    }
+
+    #[test]
+    fn test_reduce_preserves_language() {
+        // Third symbol from the end is 'a', or contains "bb"
+        let any = word("a".chars()).union(word("b".chars())).star();
+        let nfa = nth_from_end(3).union(any.clone().concat(word("bb".chars())).concat(any));
+        assert_reduced(&nfa, &['a', 'b'], 8);
+
+        // Cycles and the empty word
+        let nfa = word("ab".chars())
+            .plus()
+            .concat(word("".chars()).union(word("ba".chars()).star()));
+        assert_reduced(&nfa, &['a', 'b'], 8);
+
+        let empty = Nfa::<char>::new();
+        assert_eq!(empty.reduce().num_states(), 0);
+        assert!(empty.simulation_relation().is_empty());
+    }
+}
@@ -1,18 +1,17 @@
-use std::collections::HashSet;
-
-use multimap::MultiMap;
+use std::collections::{BTreeMap, BTreeSet};
 
 use crate::alphabet::Alphabet;
+use crate::util::id::state_id;
 
-pub type StateId = usize;
+state_id!("NFA");
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct State<A: Alphabet> {
     pub id: StateId,
     pub accepting: bool,
-    transitions: MultiMap<A, StateId>,
-    epsilon_transitions: HashSet<StateId>,
+    transitions: BTreeMap<A, BTreeSet<StateId>>,
+    epsilon_transitions: BTreeSet<StateId>,
 }
 
 impl<A: Alphabet> State<A> {
@@ -20,34 +19,66 @@ impl<A: Alphabet> State<A> {
         Self {
             id,
             accepting,
-            transitions: MultiMap::new(),
-            epsilon_transitions: HashSet::new(),
+            transitions: BTreeMap::new(),
+            epsilon_transitions: BTreeSet::new(),
         }
     }
 
     pub fn add_transition(&mut self, symbol: A, to: StateId) {
-        self.transitions.insert(symbol, to);
+        self.transitions.entry(symbol).or_default().insert(to);
     }
 
     pub fn add_epsilon_transition(&mut self, to: StateId) {
         self.epsilon_transitions.insert(to);
     }
 
+    /// Removes the transition on `symbol` to `to`, returning whether it existed.
+    pub fn remove_transition(&mut self, symbol: A, to: StateId) -> bool {
+        let Some(targets) = self.transitions.get_mut(&symbol) else {
+            return false;
+        };
+        let removed = targets.remove(&to);
+        if targets.is_empty() {
+            self.transitions.remove(&symbol);
+        }
+        removed
+    }
+
+    pub fn remove_epsilon_transition(&mut self, to: StateId) -> bool {
+        self.epsilon_transitions.remove(&to)
+    }
+
+    /// Replaces every target `to` (of both symbol and ε-transitions) by
+    /// `f(to)`, dropping transitions for which it is `None`.
+    pub(crate) fn redirect(&mut self, mut f: impl FnMut(StateId) -> Option<StateId>) {
+        let transitions = std::mem::take(&mut self.transitions);
+        for (symbol, targets) in transitions {
+            let targets: BTreeSet<StateId> = targets.into_iter().filter_map(&mut f).collect();
+            if !targets.is_empty() {
+                self.transitions.insert(symbol, targets);
+            }
+        }
+        self.epsilon_transitions = std::mem::take(&mut self.epsilon_transitions)
+            .into_iter()
+            .filter_map(&mut f)
+            .collect();
+    }
+
     pub fn num_transitions(&self) -> usize {
-        self.transitions.iter_all().map(|(_, x)| x.len()).sum()
+        self.transitions.values().map(BTreeSet::len).sum()
     }
 
     pub fn transitions(&self) -> impl Iterator<Item = (A, StateId)> + '_ {
         self.transitions
-            .flat_iter()
-            .map(|(&symbol, &to)| (symbol, to))
+            .iter()
+            .flat_map(|(&symbol, targets)| targets.iter().map(move |&to| (symbol, to)))
     }
 
-    pub fn next(&self, symbol: A) -> Option<&Vec<StateId>> {
-        self.transitions.get_vec(&symbol)
+    pub fn next(&self, symbol: A) -> Option<&BTreeSet<StateId>> {
+        self.transitions.get(&symbol)
     }
 
-    pub fn next_epsilon(&self) -> &HashSet<StateId> {
+    pub fn next_epsilon(&self) -> &BTreeSet<StateId> {
         &self.epsilon_transitions
     }
 }
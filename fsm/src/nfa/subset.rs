@@ -0,0 +1,91 @@
+use std::collections::{BTreeSet, HashMap};
+use std::hash::Hash;
+
+use crate::alphabet::Alphabet;
+use crate::dfa::Dfa;
+use crate::error::Error;
+use crate::nfa::state::StateId;
+use crate::nfa::Nfa;
+
+/// Representation of sets of NFA states the subset construction runs on.
+pub(super) trait Subsets<A: Alphabet> {
+    type Set: Clone + Eq + Hash;
+
+    /// ε-closure of the initial states.
+    fn initial(&self) -> Self::Set;
+
+    /// Successors of `states` on `symbol`, closed under ε-transitions.
+    fn post(&self, states: &Self::Set, symbol: A) -> Self::Set;
+
+    fn is_empty(&self, states: &Self::Set) -> bool;
+
+    fn any_accepting(&self, states: &Self::Set) -> bool;
+}
+
+impl<A: Alphabet> Subsets<A> for Nfa<A> {
+    type Set = BTreeSet<StateId>;
+
+    fn initial(&self) -> Self::Set {
+        self.initial_closure()
+    }
+
+    fn post(&self, states: &Self::Set, symbol: A) -> Self::Set {
+        self.post(states, symbol)
+    }
+
+    fn is_empty(&self, states: &Self::Set) -> bool {
+        states.is_empty()
+    }
+
+    fn any_accepting(&self, states: &Self::Set) -> bool {
+        self.any_accepting(states.iter().copied())
+    }
+}
+
+/// Subset construction over `alphabet`, also returning the set of NFA states
+/// each DFA state stands for, indexed by DFA state id. Fails as soon as the
+/// DFA would need more than `limit` states.
+pub(super) fn subset_construction<A: Alphabet, S: Subsets<A>>(
+    nfa: &S,
+    alphabet: &[A],
+    limit: Option<usize>,
+) -> Result<(Dfa<A>, Vec<S::Set>), Error> {
+    let mut dfa = Dfa::new();
+    let mut subsets = Vec::new();
+    let mut state_map = HashMap::new();
+    let mut queue = Vec::new();
+
+    let mut add_state = |dfa: &mut Dfa<A>, subset: &S::Set| {
+        if let Some(limit) = limit.filter(|&limit| dfa.num_states() >= limit) {
+            return Err(Error::StateLimitExceeded(limit));
+        }
+        subsets.push(subset.clone());
+        Ok(dfa.add_state(nfa.any_accepting(subset)))
+    };
+
+    let initial = nfa.initial();
+    state_map.insert(initial.clone(), add_state(&mut dfa, &initial)?);
+    queue.push(initial);
+
+    while let Some(current) = queue.pop() {
+        let current_state = state_map[&current];
+        for &symbol in alphabet {
+            let next = nfa.post(&current, symbol);
+            if nfa.is_empty(&next) {
+                continue;
+            }
+            let next_state = match state_map.get(&next) {
+                Some(&next_state) => next_state,
+                None => {
+                    let next_state = add_state(&mut dfa, &next)?;
+                    state_map.insert(next.clone(), next_state);
+                    queue.push(next);
+                    next_state
+                }
+            };
+            dfa.add_transition(current_state, symbol, next_state);
+        }
+    }
+
+    Ok((dfa, subsets))
+}
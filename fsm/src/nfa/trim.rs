@@ -0,0 +1,87 @@
+use std::collections::BTreeSet;
+
+use crate::alphabet::Alphabet;
+use crate::automaton::{self, Acceptor, Automaton};
+use crate::nfa::state::StateId;
+use crate::nfa::Nfa;
+
+impl<A: Alphabet> Nfa<A> {
+    /// Returns the states reachable from the initial states, following both
+    /// symbol and ε-transitions.
+    pub fn reachable_states(&self) -> BTreeSet<StateId> {
+        Automaton::reachable_states(self)
+    }
+
+    /// Returns the states from which an accepting state is reachable,
+    /// following both symbol and ε-transitions.
+    pub fn coreachable_states(&self) -> BTreeSet<StateId> {
+        Acceptor::coreachable_states(self)
+    }
+
+    /// Returns an equivalent NFA containing only the states that are both
+    /// reachable and co-reachable, renumbered in increasing order of their
+    /// old ids.
+    ///
+    /// The initial states are always kept, so the result of trimming an NFA
+    /// with an empty language consists of its initial states alone.
+    pub fn trim(&self) -> Nfa<A> {
+        automaton::trim(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::test_common::generate_words;
+
+    use super::*;
+
+    #[test]
+    fn test_trim() {
+        let mut nfa = Nfa::new();
+        let a = nfa.add_state(false);
+        let b = nfa.add_state(true);
+        // Unreachable state
+        let s = nfa.add_state(false);
+        // Reachable only through ε, but not co-reachable
+        let trap = nfa.add_state(false);
+        // Reachable only through ε, and co-reachable
+        let c = nfa.add_state(false);
+        nfa.add_transition(a, 'a', b);
+        nfa.add_transition(a, 'a', a);
+        nfa.add_transition(a, 'b', a);
+        nfa.add_transition(b, 'a', b);
+        nfa.add_transition(b, 'b', b);
+        nfa.add_transition(b, 'b', a);
+        nfa.add_transition(s, 'a', a);
+        nfa.add_epsilon_transition(b, trap);
+        nfa.add_transition(trap, 'c', trap);
+        nfa.add_epsilon_transition(a, c);
+        nfa.add_transition(c, 'c', b);
+
+        assert_eq!(nfa.reachable_states(), BTreeSet::from([a, b, trap, c]));
+        assert_eq!(nfa.coreachable_states(), BTreeSet::from([a, b, s, c]));
+
+        let trimmed = nfa.trim();
+        assert_eq!(trimmed.num_states(), 3);
+        assert_eq!(trimmed.num_transitions(), 7);
+        assert_eq!(trimmed.num_epsilon_transitions(), 1);
+        for word in generate_words(&['a', 'b', 'c'], 6) {
+            assert_eq!(nfa.accepts(word.clone()), trimmed.accepts(word));
+        }
+    }
+
+    #[test]
+    fn test_trim_empty_language() {
+        let mut nfa = Nfa::new();
+        let a = nfa.add_state(false);
+        let b = nfa.add_state(false);
+        let c = nfa.add_state(true);
+        nfa.add_transition(a, 'x', b);
+        nfa.add_epsilon_transition(c, a);
+
+        let trimmed = nfa.trim();
+        assert_eq!(trimmed.num_states(), 1);
+        assert_eq!(trimmed.num_transitions(), 0);
+        assert!(!trimmed.accepting(StateId::new(0)));
+    }
+}
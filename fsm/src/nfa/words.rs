@@ -0,0 +1,128 @@
+use std::collections::{BTreeSet, HashMap, VecDeque};
+
+use crate::alphabet::Alphabet;
+use crate::nfa::state::StateId;
+use crate::nfa::Nfa;
+
+impl<A: Alphabet> Nfa<A> {
+    /// Returns the shortest accepted word (the lexicographically smallest
+    /// among those of minimal length), or `None` if the language is empty.
+    ///
+    /// First computes, by a backward 0-1 BFS over states (ε-transitions have
+    /// length 0), the minimal number of symbols needed to reach acceptance
+    /// from every state. The word is then built greedily over sets of states,
+    /// choosing the smallest symbol that keeps an optimal continuation.
+    pub fn shortest_word(&self) -> Option<Vec<A>> {
+        let mut predecessors: HashMap<StateId, Vec<(Option<A>, StateId)>> = HashMap::new();
+        for (from, symbol, to) in self.transitions() {
+            predecessors
+                .entry(to.id)
+                .or_default()
+                .push((Some(symbol), from.id));
+        }
+        for (from, to) in self.epsilon_transitions() {
+            predecessors.entry(to.id).or_default().push((None, from.id));
+        }
+
+        let mut distance: HashMap<StateId, usize> = HashMap::new();
+        let mut queue: VecDeque<(StateId, usize)> = self
+            .states()
+            .filter(|state| state.accepting)
+            .map(|state| (state.id, 0))
+            .collect();
+        while let Some((state, d)) = queue.pop_front() {
+            if distance.contains_key(&state) {
+                continue;
+            }
+            distance.insert(state, d);
+            for &(symbol, from) in predecessors.get(&state).into_iter().flatten() {
+                if !distance.contains_key(&from) {
+                    match symbol {
+                        None => queue.push_front((from, d)),
+                        Some(_) => queue.push_back((from, d + 1)),
+                    }
+                }
+            }
+        }
+
+        let mut current = self.initial_closure();
+        let mut remaining = current
+            .iter()
+            .filter_map(|s| distance.get(s))
+            .min()
+            .copied()?;
+        let alphabet: BTreeSet<A> = self.transitions().map(|(_, symbol, _)| symbol).collect();
+        let mut word = Vec::with_capacity(remaining);
+        while remaining > 0 {
+            remaining -= 1;
+            let (symbol, next) = alphabet
+                .iter()
+                .map(|&symbol| {
+                    let next = self
+                        .post(&current, symbol)
+                        .into_iter()
+                        .filter(|s| distance.get(s) == Some(&remaining))
+                        .collect::<BTreeSet<_>>();
+                    (symbol, next)
+                })
+                .find(|(_, next)| !next.is_empty())
+                .expect("some successor lies on a shortest path");
+            word.push(symbol);
+            current = next;
+        }
+        Some(word)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::test_common::generate_words;
+
+    use super::*;
+
+    #[test]
+    fn test_shortest_word() {
+        let mut nfa = Nfa::new();
+        let q0 = nfa.add_state(false);
+        let q1 = nfa.add_state(false);
+        let q2 = nfa.add_state(false);
+        let q3 = nfa.add_state(true);
+        // "cb" and "ba" via ε-transitions, "a" via a longer ε-chain...
+        nfa.add_transition(q0, 'c', q1);
+        nfa.add_transition(q1, 'b', q3);
+        nfa.add_transition(q0, 'b', q2);
+        nfa.add_epsilon_transition(q2, q1);
+        nfa.add_transition(q2, 'a', q3);
+        assert_eq!(nfa.shortest_word(), Some(vec!['b', 'a']));
+
+        // ...and the empty word once the initial state reaches acceptance by ε
+        nfa.add_epsilon_transition(q0, q2);
+        nfa.add_epsilon_transition(q1, q3);
+        assert_eq!(nfa.shortest_word(), Some(vec![]));
+
+        assert_eq!(Nfa::<char>::new().shortest_word(), None);
+    }
+
+    #[test]
+    fn test_shortest_word_agrees_with_dfa() {
+        let mut nfa = Nfa::new();
+        // Words containing "ba" followed later by "ab"
+        let states = (0..5).map(|i| nfa.add_state(i == 4)).collect::<Vec<_>>();
+        for &s in &[states[0], states[2], states[4]] {
+            nfa.add_transition(s, 'a', s);
+            nfa.add_transition(s, 'b', s);
+        }
+        nfa.add_transition(states[0], 'b', states[1]);
+        nfa.add_transition(states[1], 'a', states[2]);
+        nfa.add_transition(states[2], 'a', states[3]);
+        nfa.add_transition(states[3], 'b', states[4]);
+
+        let dfa = nfa.to_dfa(&['a', 'b']);
+        assert_eq!(nfa.shortest_word(), dfa.shortest_word());
+        assert_eq!(nfa.shortest_word(), Some(vec!['b', 'a', 'a', 'b']));
+        let first = generate_words(&['a', 'b'], 5)
+            .into_iter()
+            .find(|word| nfa.accepts(word.clone()));
+        assert_eq!(nfa.shortest_word(), first);
+    }
+}
@@ -1,4 +1,5 @@
 use crate::alphabet::Alphabet;
+use crate::nfa::Nfa;
 use std::collections::VecDeque;
 
 #[inline]
@@ -27,6 +28,39 @@ pub fn generate_words<A: Alphabet>(symbols: &[A], max_length: usize) -> Vec<Vec<
     words
 }
 
+/// Words over {a, b} whose `n`-th symbol from the end is 'a', which need
+/// 2^n determinized states. State 0 is the initial one and state `n` the
+/// only accepting one.
+pub fn nth_from_end(n: usize) -> Nfa<char> {
+    let mut nfa = Nfa::new();
+    let start = nfa.add_state(false);
+    nfa.add_transition(start, 'a', start);
+    nfa.add_transition(start, 'b', start);
+    let mut last = nfa.add_state(n == 1);
+    nfa.add_transition(start, 'a', last);
+    for i in 1..n {
+        let next = nfa.add_state(i + 1 == n);
+        nfa.add_transition(last, 'a', next);
+        nfa.add_transition(last, 'b', next);
+        last = next;
+    }
+    nfa
+}
+
+/// NFA accepting exactly `word`, as a chain of states starting from the
+/// initial state 0.
+pub fn word<A: Alphabet>(word: impl IntoIterator<Item = A>) -> Nfa<A> {
+    let mut nfa = Nfa::new();
+    let mut last = nfa.add_state(false);
+    for symbol in word {
+        let next = nfa.add_state(false);
+        nfa.add_transition(last, symbol, next);
+        last = next;
+    }
+    nfa.state_mut(last).accepting = true;
+    nfa
+}
+
 pub fn generate_strings(symbols: &[char], max_length: usize) -> Vec<String> {
     let mut words = Vec::new();
     let mut queue = VecDeque::from(["".to_string()]);
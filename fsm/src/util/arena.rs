@@ -1,18 +1,28 @@
+use std::collections::HashMap;
+use std::iter::Flatten;
 use std::ops::{Index, IndexMut};
 use std::vec::IntoIter;
 
-#[derive(Debug)]
+/// Slot-based storage handing out `usize` ids.
+///
+/// Removed slots are put on a free list and reused by later allocations,
+/// so ids stay small but are not necessarily contiguous. [`Arena::compact`]
+/// closes the gaps left by removals.
+///
+/// Ids carry no generation: once an item is removed, a stale copy of its id
+/// refers to whatever item is allocated in the slot next.
+#[derive(Debug, Clone)]
 pub struct Arena<T> {
-    items: Vec<T>,
+    items: Vec<Option<T>>,
+    free: Vec<usize>,
 }
 
 impl<T> Arena<T> {
     pub fn new() -> Self {
-        Self { items: Vec::new() }
-    }
-
-    pub fn alloc(&mut self, item: T) -> usize {
-        self.alloc_with_id(|_| item)
+        Self {
+            items: Vec::new(),
+            free: Vec::new(),
+        }
     }
 
     pub fn alloc_with_id<F>(&mut self, f: F) -> usize
@@ -20,28 +30,72 @@ impl<T> Arena<T> {
         F: FnOnce(usize) -> T,
     {
         let id = self.next_id();
-        self.items.push(f(id));
+        let item = Some(f(id));
+        if self.free.pop().is_some() {
+            self.items[id] = item;
+        } else {
+            self.items.push(item);
+        }
         id
     }
 
     pub fn next_id(&self) -> usize {
+        self.free.last().copied().unwrap_or(self.items.len())
+    }
+
+    /// Exclusive upper bound on the ids of all live items.
+    pub fn id_bound(&self) -> usize {
         self.items.len()
     }
 
     pub fn len(&self) -> usize {
-        self.items.len()
+        self.items.len() - self.free.len()
     }
 
     pub fn is_empty(&self) -> bool {
-        self.items.is_empty()
+        self.len() == 0
+    }
+
+    pub fn contains(&self, id: usize) -> bool {
+        self.get(id).is_some()
+    }
+
+    pub fn get(&self, id: usize) -> Option<&T> {
+        self.items.get(id).and_then(Option::as_ref)
+    }
+
+    pub fn get_mut(&mut self, id: usize) -> Option<&mut T> {
+        self.items.get_mut(id).and_then(Option::as_mut)
+    }
+
+    /// Removes the item with the given id, making the id available again.
+    pub fn remove(&mut self, id: usize) -> Option<T> {
+        let item = self.items.get_mut(id)?.take()?;
+        self.free.push(id);
+        Some(item)
+    }
+
+    /// Moves all live items to the front, preserving their order, and
+    /// returns the mapping from old to new ids.
+    pub fn compact(&mut self) -> HashMap<usize, usize> {
+        let items = std::mem::take(&mut self.items);
+        self.free.clear();
+        let mut old2new = HashMap::new();
+        for (old, item) in items.into_iter().enumerate() {
+            if let Some(item) = item {
+                old2new.insert(old, self.items.len());
+                self.items.push(Some(item));
+            }
+        }
+        old2new
     }
 
     pub fn iter(&self) -> impl Iterator<Item = &T> {
-        self.items.iter()
+        self.items.iter().flatten()
     }
 
     pub fn iter_mut(&mut self) -> impl Iterator<Item = &mut T> {
-        self.items.iter_mut()
+        self.items.iter_mut().flatten()
     }
 }
 
@@ -55,21 +109,53 @@ impl<T> Index<usize> for Arena<T> {
     type Output = T;
 
     fn index(&self, index: usize) -> &Self::Output {
-        &self.items[index]
+        self.get(index)
+            .unwrap_or_else(|| panic!("no item with id {}", index))
     }
 }
 
 impl<T> IndexMut<usize> for Arena<T> {
     fn index_mut(&mut self, index: usize) -> &mut Self::Output {
-        &mut self.items[index]
+        self.get_mut(index)
+            .unwrap_or_else(|| panic!("no item with id {}", index))
     }
 }
 
 impl<T> IntoIterator for Arena<T> {
     type Item = T;
-    type IntoIter = IntoIter<Self::Item>;
+    type IntoIter = Flatten<IntoIter<Option<T>>>;
 
     fn into_iter(self) -> Self::IntoIter {
-        self.items.into_iter()
+        self.items.into_iter().flatten()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_remove_and_reuse() {
+        let mut arena = Arena::new();
+        let a = arena.alloc_with_id(|_| 'a');
+        let b = arena.alloc_with_id(|_| 'b');
+        let c = arena.alloc_with_id(|_| 'c');
+        assert_eq!(arena.remove(b), Some('b'));
+        assert_eq!(arena.remove(b), None);
+        assert_eq!(arena.len(), 2);
+        assert!(!arena.contains(b));
+        assert_eq!(arena.iter().collect::<Vec<_>>(), vec![&'a', &'c']);
+
+        assert_eq!(arena.next_id(), b);
+        let d = arena.alloc_with_id(|_| 'd');
+        assert_eq!(d, b);
+        assert_eq!(arena[d], 'd');
+        assert_eq!(arena.id_bound(), 3);
+
+        arena.remove(a);
+        let old2new = arena.compact();
+        assert_eq!(old2new, HashMap::from([(c, 1), (d, 0)]));
+        assert_eq!(arena.iter().collect::<Vec<_>>(), vec![&'d', &'c']);
+        assert_eq!(arena.next_id(), 2);
     }
 }
@@ -0,0 +1,70 @@
+use graphviz_rust::dot_generator::{attr, edge, id, node, node_id, stmt};
+use graphviz_rust::dot_structures::{
+    Attribute, Edge, EdgeTy, Graph, Id, Node, NodeId, Stmt, Vertex,
+};
+use graphviz_rust::printer::{DotPrinter, PrinterContext};
+
+use crate::automaton::Automaton;
+
+/// Graph-level settings of [`render`].
+pub struct Style<'a> {
+    pub name: &'a str,
+    pub strict: bool,
+    /// Attributes of the `start` node pointing to the initial states.
+    pub start: Vec<Attribute>,
+}
+
+/// Renders any machine as a left-to-right digraph.
+///
+/// `node_attrs` gives the attributes of every state. `transitions` lists the
+/// edges in the order they are printed, each with the data `edge_attrs`
+/// turns into its attributes.
+pub fn render<M: Automaton, T>(
+    machine: &M,
+    style: Style,
+    node_attrs: impl Fn(M::StateId) -> Vec<Attribute>,
+    transitions: impl IntoIterator<Item = (M::StateId, T, M::StateId)>,
+    edge_attrs: impl Fn(T) -> Vec<Attribute>,
+) -> String {
+    let mut stmts = Vec::new();
+
+    stmts.push(stmt!(attr!("rankdir", "LR")));
+
+    // States:
+    for state in machine.state_ids() {
+        let node = node!(state, node_attrs(state));
+        stmts.push(stmt!(node));
+    }
+
+    // Initial states:
+    let mut initial = machine.initial_state_ids().peekable();
+    if initial.peek().is_some() {
+        let node = node!("start", style.start);
+        stmts.push(stmt!(node));
+    }
+    for state in initial {
+        let edge = edge!( node_id!("start") => node_id!(state) );
+        stmts.push(stmt!(edge));
+    }
+
+    // Transitions:
+    for (from, data, to) in transitions {
+        let mut edge = edge!( node_id!(from) => node_id!(to) );
+        edge.attributes = edge_attrs(data);
+        stmts.push(stmt!(edge));
+    }
+
+    let g = Graph::DiGraph {
+        id: id!(style.name),
+        strict: style.strict,
+        stmts,
+    };
+    let mut ctx = PrinterContext::default();
+    ctx.with_semi();
+    g.print(&mut ctx)
+}
+
+/// Escapes `label` for use inside a quoted DOT string.
+pub fn quote(label: &str) -> String {
+    label.replace('\\', "\\\\").replace('"', "\\\"")
+}
@@ -0,0 +1,45 @@
+/// Defines a `StateId` newtype over `usize`, so that ids of different kinds
+/// of machines cannot be mixed up.
+///
+/// The id serializes as the bare index and displays as it.
+macro_rules! state_id {
+    ($machine:literal) => {
+        #[doc = concat!("Identifier of a state of a ", $machine, ".")]
+        ///
+        /// Ids are handed out by the machine when adding states. An id built
+        /// with [`StateId::new`] is only meaningful for the machine whose
+        /// state has that index. The id of a removed state is reused by the
+        /// next state added, so ids of removed states must not be kept.
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+        #[cfg_attr(
+            feature = "serde",
+            derive(serde::Serialize, serde::Deserialize),
+            serde(transparent)
+        )]
+        pub struct StateId(usize);
+
+        impl StateId {
+            pub const fn new(index: usize) -> Self {
+                Self(index)
+            }
+
+            pub const fn index(self) -> usize {
+                self.0
+            }
+        }
+
+        impl From<StateId> for usize {
+            fn from(id: StateId) -> usize {
+                id.0
+            }
+        }
+
+        impl std::fmt::Display for StateId {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                write!(f, "{}", self.0)
+            }
+        }
+    };
+}
+
+pub(crate) use state_id;
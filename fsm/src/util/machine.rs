@@ -0,0 +1,123 @@
+/// Implements the state storage shared by all machines: construction,
+/// lookup of states by id, and removal and renumbering of states.
+///
+/// The machine must have the fields `states: Arena<$state>` and `initial`,
+/// the latter being `Default`, and all its generic parameters must be
+/// [`Alphabet`](crate::alphabet::Alphabet)s. The state type must have an
+/// `id` field and a `redirect` method.
+macro_rules! impl_states {
+    ($machine:ident<$($param:ident),+>, $state:ty, $id:ty) => {
+        impl<$($param: $crate::alphabet::Alphabet),+> $machine<$($param),+> {
+            pub fn new() -> Self {
+                Self {
+                    states: $crate::util::arena::Arena::new(),
+                    initial: Default::default(),
+                }
+            }
+
+            /// Allocates a state built by `make` from its id.
+            fn alloc_state(&mut self, make: impl FnOnce($id) -> $state) -> $id {
+                <$id>::new(self.states.alloc_with_id(|id| make(<$id>::new(id))))
+            }
+
+            /// Removes `state` from the arena and replaces every transition
+            /// target `to` of the remaining states by `redirect(to)`, dropping
+            /// transitions for which it is `None`.
+            fn detach_state(
+                &mut self,
+                state: $id,
+                redirect: impl Fn($id) -> Option<$id>,
+            ) -> Result<$state, $crate::error::Error> {
+                let removed = self
+                    .states
+                    .remove(state.index())
+                    .ok_or($crate::error::Error::UnknownState(state.index()))?;
+                for other in self.states.iter_mut() {
+                    other.redirect(&redirect);
+                }
+                Ok(removed)
+            }
+
+            /// Closes the gaps in the arena and renumbers the states and their
+            /// transitions accordingly, returning the mapping from old to new
+            /// ids so that the caller can renumber the initial states.
+            fn compact_states(&mut self) -> std::collections::HashMap<$id, $id> {
+                let old2new: std::collections::HashMap<$id, $id> = self
+                    .states
+                    .compact()
+                    .into_iter()
+                    .map(|(old, new)| (<$id>::new(old), <$id>::new(new)))
+                    .collect();
+                for state in self.states.iter_mut() {
+                    state.id = old2new[&state.id];
+                    state.redirect(|to| old2new.get(&to).copied());
+                }
+                old2new
+            }
+
+            pub fn state(&self, index: $id) -> &$state {
+                self.try_state(index).unwrap_or_else(|e| panic!("{}", e))
+            }
+            pub fn state_mut(&mut self, index: $id) -> &mut $state {
+                self.try_state_mut(index)
+                    .unwrap_or_else(|e| panic!("{}", e))
+            }
+
+            pub fn try_state(&self, index: $id) -> Result<&$state, $crate::error::Error> {
+                self.states
+                    .get(index.index())
+                    .ok_or($crate::error::Error::UnknownState(index.index()))
+            }
+            pub fn try_state_mut(
+                &mut self,
+                index: $id,
+            ) -> Result<&mut $state, $crate::error::Error> {
+                self.states
+                    .get_mut(index.index())
+                    .ok_or($crate::error::Error::UnknownState(index.index()))
+            }
+
+            fn check_state(&self, state: $id) -> Result<(), $crate::error::Error> {
+                if self.states.contains(state.index()) {
+                    Ok(())
+                } else {
+                    Err($crate::error::Error::UnknownState(state.index()))
+                }
+            }
+
+            pub fn num_states(&self) -> usize {
+                self.states.len()
+            }
+
+            pub fn states(&self) -> impl Iterator<Item = &$state> {
+                self.states.iter()
+            }
+        }
+
+        impl<$($param: $crate::alphabet::Alphabet),+> Default for $machine<$($param),+> {
+            fn default() -> Self {
+                Self::new()
+            }
+        }
+
+        impl<$($param: $crate::alphabet::Alphabet),+> std::ops::Index<$id>
+            for $machine<$($param),+>
+        {
+            type Output = $state;
+
+            fn index(&self, index: $id) -> &Self::Output {
+                self.state(index)
+            }
+        }
+
+        impl<$($param: $crate::alphabet::Alphabet),+> std::ops::IndexMut<$id>
+            for $machine<$($param),+>
+        {
+            fn index_mut(&mut self, index: $id) -> &mut Self::Output {
+                self.state_mut(index)
+            }
+        }
+    };
+}
+
+pub(crate) use impl_states;
@@ -1,3 +1,11 @@
 pub mod arena;
+pub mod bitset;
 pub mod dfs;
+pub mod graphviz;
+pub mod id;
+pub mod machine;
+pub mod partition;
+pub mod regex;
+#[cfg(feature = "serde")]
+pub mod serde;
 pub mod set;
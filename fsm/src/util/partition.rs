@@ -0,0 +1,234 @@
+use std::collections::HashMap;
+
+struct Block {
+    states: Vec<usize>,
+    /// Compound block (union of blocks used as a past splitter) it belongs to.
+    compound: usize,
+}
+
+/// Partition of `0..n` being refined, with the compound blocks of
+/// Paige–Tarjan's algorithm on top of it.
+struct Refinement {
+    block_of: Vec<usize>,
+    /// Position of each state in the list of its block.
+    position: Vec<usize>,
+    blocks: Vec<Block>,
+    compounds: Vec<Vec<usize>>,
+    /// Compound blocks made of at least two blocks.
+    pending: Vec<usize>,
+}
+
+impl Refinement {
+    fn new(n: usize) -> Self {
+        Self {
+            block_of: vec![0; n],
+            position: (0..n).collect(),
+            blocks: vec![Block {
+                states: (0..n).collect(),
+                compound: 0,
+            }],
+            compounds: vec![vec![0]],
+            pending: Vec::new(),
+        }
+    }
+
+    /// Splits every block into its states in `marked` and the others.
+    /// `marked` must not contain duplicates.
+    fn split(&mut self, marked: &[usize]) {
+        let mut counts: HashMap<usize, usize> = HashMap::new();
+        for &state in marked {
+            *counts.entry(self.block_of[state]).or_default() += 1;
+        }
+        counts.retain(|&block, &mut count| count < self.blocks[block].states.len());
+        let mut new_blocks = HashMap::new();
+        for &state in marked {
+            let old = self.block_of[state];
+            if !counts.contains_key(&old) {
+                continue;
+            }
+            let new = *new_blocks.entry(old).or_insert_with(|| {
+                let compound = self.blocks[old].compound;
+                let new = self.blocks.len();
+                self.blocks.push(Block {
+                    states: Vec::new(),
+                    compound,
+                });
+                self.compounds[compound].push(new);
+                if self.compounds[compound].len() == 2 {
+                    self.pending.push(compound);
+                }
+                new
+            });
+            let states = &mut self.blocks[old].states;
+            let position = self.position[state];
+            states.swap_remove(position);
+            if let Some(&moved) = states.get(position) {
+                self.position[moved] = position;
+            }
+            self.position[state] = self.blocks[new].states.len();
+            self.blocks[new].states.push(state);
+            self.block_of[state] = new;
+        }
+    }
+}
+
+/// Coarsest partition of the states `0..accepting.len()` that separates
+/// accepting from non-accepting states and is stable for `edges`: states of
+/// a same block have edges with the same labels into the same blocks.
+/// Returns the block of each state, which is the class of the largest
+/// (strong) bisimulation of the labelled transition system.
+///
+/// This is Paige and Tarjan's algorithm, running in `O(m log n)` for `m`
+/// edges: blocks are split against the smaller half of a compound splitter,
+/// and per-state edge counts give the split against the other half for free.
+pub fn coarsest_partition<L: Ord + Copy>(
+    accepting: &[bool],
+    edges: &[(usize, L, usize)],
+) -> Vec<usize> {
+    let n = accepting.len();
+    let mut refinement = Refinement::new(n);
+    let marked: Vec<usize> = (0..n).filter(|&s| accepting[s]).collect();
+    refinement.split(&marked);
+
+    // Edges sorted by label, so that the edges into a block can be grouped
+    // by label by sorting their indices
+    let mut edges = edges.to_vec();
+    edges.sort_unstable_by_key(|&(from, label, to)| (label, from, to));
+    edges.dedup();
+    let mut incoming = vec![Vec::new(); n];
+    for (e, &(_, _, to)) in edges.iter().enumerate() {
+        incoming[to].push(e);
+    }
+
+    // Stability against the single compound block made of all states, with
+    // one count per state and label of its edges into it
+    let mut counts = Vec::new();
+    let mut edge_count = Vec::with_capacity(edges.len());
+    for group in edges.chunk_by(|a, b| a.1 == b.1) {
+        let mut sources = Vec::new();
+        for edges_from in group.chunk_by(|a, b| a.0 == b.0) {
+            sources.push(edges_from[0].0);
+            counts.push(edges_from.len());
+            edge_count.extend(std::iter::repeat_n(counts.len() - 1, edges_from.len()));
+        }
+        refinement.split(&sources);
+    }
+
+    while let Some(compound) = refinement.pending.pop() {
+        // Take the smaller of the last two blocks out of the compound
+        let blocks = &mut refinement.compounds[compound];
+        let len = blocks.len();
+        let size = |block: usize| refinement.blocks[block].states.len();
+        if size(blocks[len - 2]) < size(blocks[len - 1]) {
+            blocks.swap(len - 2, len - 1);
+        }
+        let splitter = blocks.pop().unwrap();
+        if blocks.len() >= 2 {
+            refinement.pending.push(compound);
+        }
+        refinement.blocks[splitter].compound = refinement.compounds.len();
+        refinement.compounds.push(vec![splitter]);
+
+        let mut into_splitter: Vec<usize> = refinement.blocks[splitter]
+            .states
+            .iter()
+            .flat_map(|&to| incoming[to].iter().copied())
+            .collect();
+        into_splitter.sort_unstable();
+        for group in into_splitter.chunk_by(|&a, &b| edges[a].1 == edges[b].1) {
+            // Count the edges of each source into the splitter
+            let mut sources = Vec::new();
+            let mut records: HashMap<usize, (usize, usize)> = HashMap::new();
+            for &e in group {
+                let from = edges[e].0;
+                let (record, _) = *records.entry(from).or_insert_with(|| {
+                    sources.push(from);
+                    counts.push(0);
+                    (counts.len() - 1, edge_count[e])
+                });
+                counts[record] += 1;
+            }
+            refinement.split(&sources);
+
+            // Sources with no edge into the rest of the compound
+            let only_splitter: Vec<usize> = sources
+                .iter()
+                .copied()
+                .filter(|from| {
+                    let (record, old) = records[from];
+                    counts[record] == counts[old]
+                })
+                .collect();
+            refinement.split(&only_splitter);
+
+            for (record, old) in records.values() {
+                counts[*old] -= counts[*record];
+            }
+            for &e in group {
+                edge_count[e] = records[&edges[e].0].0;
+            }
+        }
+    }
+
+    refinement.block_of
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn same_block(blocks: &[usize], a: usize, b: usize) -> bool {
+        blocks[a] == blocks[b]
+    }
+
+    #[test]
+    fn test_coarsest_partition() {
+        // 0 -a-> 1 -b-> 2 and 0 -a-> 3, versus 4 -a-> 5 -b-> 6 and
+        // 7 -a-> 8 -b-> 9, 7 -a-> 10: `0` and `4` are not bisimilar, since
+        // `3` has no transitions while `5` has one, but `0` and `7` are.
+        let edges = [
+            (0, 'a', 1),
+            (1, 'b', 2),
+            (0, 'a', 3),
+            (4, 'a', 5),
+            (5, 'b', 6),
+            (7, 'a', 8),
+            (8, 'b', 9),
+            (7, 'a', 10),
+        ];
+        let accepting = [
+            false, false, true, true, false, false, true, false, false, true, true,
+        ];
+        let blocks = coarsest_partition(&accepting, &edges);
+        assert!(same_block(&blocks, 0, 7));
+        assert!(same_block(&blocks, 1, 5) && same_block(&blocks, 5, 8));
+        assert!(same_block(&blocks, 2, 3) && same_block(&blocks, 3, 10));
+        assert!(!same_block(&blocks, 0, 4));
+        assert!(!same_block(&blocks, 1, 2));
+    }
+
+    #[test]
+    fn test_coarsest_partition_cycles() {
+        // Cycles of length 2 and 4 with every other state accepting, and
+        // a cycle of length 3 with one accepting state
+        let edges = [
+            (0, (), 1),
+            (1, (), 0),
+            (2, (), 3),
+            (3, (), 4),
+            (4, (), 5),
+            (5, (), 2),
+            (6, (), 7),
+            (7, (), 8),
+            (8, (), 6),
+        ];
+        let accepting = [true, false, true, false, true, false, true, false, false];
+        let blocks = coarsest_partition(&accepting, &edges);
+        assert!(same_block(&blocks, 0, 2) && same_block(&blocks, 2, 4));
+        assert!(same_block(&blocks, 1, 3) && same_block(&blocks, 3, 5));
+        assert!(!same_block(&blocks, 0, 1));
+        assert!(!same_block(&blocks, 0, 6));
+        assert!(!same_block(&blocks, 7, 8));
+        assert!(coarsest_partition::<()>(&[], &[]).is_empty());
+    }
+}
@@ -0,0 +1,336 @@
+use std::collections::BTreeMap;
+use std::fmt::{self, Display, Formatter};
+
+/// Regular expression built by state elimination.
+///
+/// The smart constructors keep expressions in a normal form: unions are
+/// flattened, sorted and deduplicated, ε is dropped from concatenations,
+/// and a few identities such as `(r*)* = r*` and `r r* = r+` are applied.
+/// The empty language has no representation; it is `None` in the callers.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Regex<A> {
+    Epsilon,
+    Symbol(A),
+    /// At least two factors, none of them `Epsilon` or `Concat`.
+    Concat(Vec<Regex<A>>),
+    /// At least two sorted, distinct alternatives, none of them `Union`.
+    Union(Vec<Regex<A>>),
+    Star(Box<Regex<A>>),
+    Plus(Box<Regex<A>>),
+}
+
+impl<A: Ord + Clone> Regex<A> {
+    fn nullable(&self) -> bool {
+        match self {
+            Regex::Epsilon | Regex::Star(_) => true,
+            Regex::Symbol(_) => false,
+            Regex::Concat(factors) => factors.iter().all(Regex::nullable),
+            Regex::Union(alternatives) => alternatives.iter().any(Regex::nullable),
+            Regex::Plus(inner) => inner.nullable(),
+        }
+    }
+
+    fn into_factors(self) -> Vec<Regex<A>> {
+        match self {
+            Regex::Epsilon => Vec::new(),
+            Regex::Concat(factors) => factors,
+            other => vec![other],
+        }
+    }
+
+    fn into_alternatives(self) -> Vec<Regex<A>> {
+        match self {
+            Regex::Union(alternatives) => alternatives,
+            other => vec![other],
+        }
+    }
+
+    pub fn union(self, other: Regex<A>) -> Regex<A> {
+        let mut alternatives = self.into_alternatives();
+        alternatives.extend(other.into_alternatives());
+        alternatives.sort();
+        alternatives.dedup();
+        // ε is redundant next to any other nullable alternative
+        if alternatives.len() > 1
+            && alternatives
+                .iter()
+                .any(|r| *r != Regex::Epsilon && r.nullable())
+        {
+            alternatives.retain(|r| *r != Regex::Epsilon);
+        }
+        if alternatives.len() == 1 {
+            alternatives.pop().unwrap()
+        } else {
+            Regex::Union(alternatives)
+        }
+    }
+
+    pub fn concat(self, other: Regex<A>) -> Regex<A> {
+        let mut factors: Vec<Regex<A>> = Vec::new();
+        for factor in self.into_factors().into_iter().chain(other.into_factors()) {
+            match (factors.last(), factor) {
+                // r* r* = r*
+                (Some(Regex::Star(last)), Regex::Star(inner)) if **last == *inner => {}
+                // r* r = r+
+                (Some(Regex::Star(last)), factor) if **last == factor => {
+                    let last = factors.pop().unwrap();
+                    if let Regex::Star(inner) = last {
+                        factors.push(Regex::Plus(inner));
+                    }
+                }
+                // r r* = r+, where r may span several factors
+                (_, Regex::Star(inner)) => {
+                    let prefix = inner.as_ref().clone().into_factors();
+                    if !prefix.is_empty() && factors.ends_with(&prefix) {
+                        factors.truncate(factors.len() - prefix.len());
+                        factors.push(Regex::Plus(inner));
+                    } else {
+                        factors.push(Regex::Star(inner));
+                    }
+                }
+                (_, factor) => factors.push(factor),
+            }
+        }
+        match factors.len() {
+            0 => Regex::Epsilon,
+            1 => factors.pop().unwrap(),
+            _ => Regex::Concat(factors),
+        }
+    }
+
+    pub fn star(self) -> Regex<A> {
+        match self {
+            Regex::Epsilon => Regex::Epsilon,
+            Regex::Star(inner) | Regex::Plus(inner) => Regex::Star(inner),
+            Regex::Union(alternatives) if alternatives.contains(&Regex::Epsilon) => alternatives
+                .into_iter()
+                .filter(|r| *r != Regex::Epsilon)
+                .reduce(Regex::union)
+                .map_or(Regex::Epsilon, Regex::star),
+            other => Regex::Star(Box::new(other)),
+        }
+    }
+}
+
+/// Characters that symbols are escaped with a backslash for.
+const METACHARACTERS: &str = "|*+?().\\";
+
+impl<A: Display> Regex<A> {
+    /// Writes the expression, parenthesizing it if its top-level operator
+    /// binds looser than `precedence` (0: union, 1: concatenation, 2: postfix).
+    fn write(&self, f: &mut Formatter<'_>, precedence: u8) -> fmt::Result {
+        match self {
+            Regex::Epsilon => Ok(()),
+            Regex::Symbol(symbol) => {
+                for c in symbol.to_string().chars() {
+                    if METACHARACTERS.contains(c) {
+                        write!(f, "\\")?;
+                    }
+                    write!(f, "{}", c)?;
+                }
+                Ok(())
+            }
+            Regex::Concat(factors) => {
+                if precedence > 1 {
+                    write!(f, "(")?;
+                }
+                for factor in factors {
+                    factor.write(f, 1)?;
+                }
+                if precedence > 1 {
+                    write!(f, ")")?;
+                }
+                Ok(())
+            }
+            Regex::Union(alternatives)
+                if alternatives.iter().any(|r| matches!(r, Regex::Epsilon)) =>
+            {
+                let rest: Vec<_> = alternatives
+                    .iter()
+                    .filter(|r| !matches!(r, Regex::Epsilon))
+                    .collect();
+                if rest.len() > 1 {
+                    write!(f, "(")?;
+                    for (i, alternative) in rest.iter().enumerate() {
+                        if i > 0 {
+                            write!(f, "|")?;
+                        }
+                        alternative.write(f, 0)?;
+                    }
+                    write!(f, ")")?;
+                } else {
+                    rest[0].write(f, 2)?;
+                }
+                write!(f, "?")
+            }
+            Regex::Union(alternatives) => {
+                if precedence > 0 {
+                    write!(f, "(")?;
+                }
+                for (i, alternative) in alternatives.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, "|")?;
+                    }
+                    alternative.write(f, 0)?;
+                }
+                if precedence > 0 {
+                    write!(f, ")")?;
+                }
+                Ok(())
+            }
+            Regex::Star(inner) => {
+                inner.write(f, 2)?;
+                write!(f, "*")
+            }
+            Regex::Plus(inner) => {
+                inner.write(f, 2)?;
+                write!(f, "+")
+            }
+        }
+    }
+}
+
+impl<A: Display> Display for Regex<A> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        self.write(f, 0)
+    }
+}
+
+/// Converts an automaton with states `0..num_states` and the given initial
+/// states into a regular expression by state elimination, or returns `None`
+/// if its language is empty.
+///
+/// Edges labelled `None` are ε-transitions. States are eliminated greedily,
+/// always picking the one with the fewest `predecessors × successors` pairs,
+/// which tends to keep the intermediate expressions small.
+pub fn state_elimination<A: Ord + Clone>(
+    num_states: usize,
+    initial: impl IntoIterator<Item = usize>,
+    accepting: impl Fn(usize) -> bool,
+    edges: impl IntoIterator<Item = (usize, Option<A>, usize)>,
+) -> Option<Regex<A>> {
+    // Generalized automaton over states `0..num_states` plus a fresh
+    // start and a fresh final state.
+    let start = num_states;
+    let end = num_states + 1;
+    let mut labels: BTreeMap<(usize, usize), Regex<A>> = BTreeMap::new();
+    let add = |labels: &mut BTreeMap<_, _>, from: usize, to: usize, regex: Regex<A>| {
+        let label = match labels.remove(&(from, to)) {
+            Some(existing) => Regex::union(existing, regex),
+            None => regex,
+        };
+        labels.insert((from, to), label);
+    };
+
+    for state in initial {
+        add(&mut labels, start, state, Regex::Epsilon);
+    }
+    for state in (0..num_states).filter(|&s| accepting(s)) {
+        add(&mut labels, state, end, Regex::Epsilon);
+    }
+    for (from, symbol, to) in edges {
+        let regex = symbol.map_or(Regex::Epsilon, Regex::Symbol);
+        add(&mut labels, from, to, regex);
+    }
+
+    let mut remaining: Vec<usize> = (0..num_states).collect();
+    while !remaining.is_empty() {
+        let cost = |q: usize| {
+            let incoming = labels.keys().filter(|&&(p, r)| r == q && p != q).count();
+            let outgoing = labels.keys().filter(|&&(p, r)| p == q && r != q).count();
+            incoming * outgoing
+        };
+        let (position, _) = remaining
+            .iter()
+            .enumerate()
+            .min_by_key(|&(_, &q)| cost(q))
+            .unwrap();
+        let q = remaining.remove(position);
+
+        let self_loop = labels.remove(&(q, q)).map(Regex::star);
+        let incoming: Vec<(usize, Regex<A>)> = labels
+            .iter()
+            .filter(|(&(_, r), _)| r == q)
+            .map(|(&(p, _), regex)| (p, regex.clone()))
+            .collect();
+        let outgoing: Vec<(usize, Regex<A>)> = labels
+            .iter()
+            .filter(|(&(p, _), _)| p == q)
+            .map(|(&(_, r), regex)| (r, regex.clone()))
+            .collect();
+        labels.retain(|&(p, r), _| p != q && r != q);
+
+        for (p, into) in &incoming {
+            for (r, out) in &outgoing {
+                let mut path = into.clone();
+                if let Some(self_loop) = &self_loop {
+                    path = path.concat(self_loop.clone());
+                }
+                add(&mut labels, *p, *r, path.concat(out.clone()));
+            }
+        }
+    }
+
+    labels.remove(&(start, end))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn symbol(c: char) -> Regex<char> {
+        Regex::Symbol(c)
+    }
+
+    #[test]
+    fn test_simplification() {
+        let a = symbol('a');
+        let b = symbol('b');
+        assert_eq!(a.clone().union(a.clone()), a);
+        assert_eq!(Regex::Epsilon.concat(a.clone()), a);
+        assert_eq!(a.clone().star().star(), a.clone().star());
+        assert_eq!(
+            a.clone().concat(a.clone().star()),
+            Regex::Plus(Box::new(a.clone()))
+        );
+        assert_eq!(Regex::Epsilon.union(a.clone()).star(), a.clone().star());
+        assert_eq!(Regex::Epsilon.union(a.clone().star()), a.clone().star());
+        let ab = a.clone().concat(b.clone());
+        assert_eq!(ab.clone().concat(ab.clone().star()).to_string(), "(ab)+");
+    }
+
+    #[test]
+    fn test_display() {
+        let a = symbol('a');
+        let b = symbol('b');
+        let c = symbol('c');
+        let regex = a
+            .clone()
+            .concat(b.clone().union(c.clone()).star())
+            .concat(Regex::Epsilon.union(a.clone()));
+        assert_eq!(regex.to_string(), "a(b|c)*a?");
+        let regex = Regex::Epsilon.union(a.clone()).union(b.concat(c));
+        assert_eq!(regex.to_string(), "(a|bc)?");
+        assert_eq!(Regex::<char>::Epsilon.to_string(), "");
+        let regex = symbol('(').concat(symbol('.').union(symbol('\\')).star());
+        assert_eq!(regex.to_string(), "\\((\\.|\\\\)*");
+    }
+
+    #[test]
+    fn test_state_elimination() {
+        // 0 --a--> 1 --b--> 0, accepting 1
+        let regex = state_elimination(2, [0], |s| s == 1, [(0, Some('a'), 1), (1, Some('b'), 0)]);
+        assert_eq!(regex.unwrap().to_string(), "a(ba)*");
+
+        let regex = state_elimination(2, [0], |_| false, [(0, Some('a'), 1)]);
+        assert_eq!(regex, None);
+
+        let regex = state_elimination(
+            1,
+            [0],
+            |_| true,
+            std::iter::empty::<(usize, Option<char>, usize)>(),
+        );
+        assert_eq!(regex, Some(Regex::Epsilon));
+    }
+}
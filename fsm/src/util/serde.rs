@@ -0,0 +1,39 @@
+use std::collections::HashMap;
+use std::hash::Hash;
+
+use serde::{Deserialize, Deserializer};
+
+use crate::error::Error;
+
+/// Deserializes a field that is wrapped in one more `Option`, so that with
+/// `#[serde(default)]` an absent field is `None` while `null` is `Some(None)`.
+pub fn present<'de, T: Deserialize<'de>, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Option<T>, D::Error> {
+    T::deserialize(deserializer).map(Some)
+}
+
+/// Mapping from the ids of deserialized states to the ids they got when
+/// added to the new machine.
+pub struct Renumbering<Id>(HashMap<Id, Id>);
+
+impl<Id: Copy + Eq + Hash + Into<usize>> Renumbering<Id> {
+    /// Collects pairs of old and new ids, rejecting duplicate old ids.
+    pub fn new(ids: impl IntoIterator<Item = (Id, Id)>) -> Result<Self, Error> {
+        let mut old2new = HashMap::new();
+        for (old, new) in ids {
+            if old2new.insert(old, new).is_some() {
+                return Err(Error::DuplicateState(old.into()));
+            }
+        }
+        Ok(Self(old2new))
+    }
+
+    /// Returns the new id of `old`, rejecting ids of states never listed.
+    pub fn get(&self, old: Id) -> Result<Id, Error> {
+        self.0
+            .get(&old)
+            .copied()
+            .ok_or(Error::UnknownState(old.into()))
+    }
+}
@@ -2,10 +2,14 @@ use std::collections::{BTreeSet, HashMap, HashSet};
 use std::ops::{Index, IndexMut};
 
 #[derive(Debug)]
+#[allow(dead_code)]
 pub struct State {
     id: usize,
     accepting: bool,
     transitions: HashMap<char, usize>,
+    /// Target of the `.` transition, taken on any symbol without a
+    /// transition of its own.
+    wildcard: Option<usize>,
     epsilon_transitions: HashSet<usize>,
 }
 
@@ -15,6 +19,7 @@ impl State {
             id,
             accepting,
             transitions: HashMap::new(),
+            wildcard: None,
             epsilon_transitions: HashSet::new(),
         }
     }
@@ -51,6 +56,12 @@ impl Nfa {
     }
 }
 
+impl Default for Nfa {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl Index<usize> for Nfa {
     type Output = State;
 
@@ -69,7 +80,8 @@ impl Nfa {
     pub fn parse(&mut self, pattern: &str) -> Fragment {
         let postfix = to_postfix(&insert_explicit_concat_operator(pattern));
         let mut stack = Vec::new();
-        for token in postfix.chars() {
+        let mut tokens = postfix.chars();
+        while let Some(token) = tokens.next() {
             match token {
                 '\0' => {
                     let f2 = stack.pop().unwrap();
@@ -85,6 +97,22 @@ impl Nfa {
                     let f = stack.pop().unwrap();
                     stack.push(self.closure(f));
                 }
+                '+' => {
+                    let f = stack.pop().unwrap();
+                    stack.push(self.plus(f));
+                }
+                '?' => {
+                    let f = stack.pop().unwrap();
+                    stack.push(self.optional(f));
+                }
+                '\\' => {
+                    // A trailing backslash stands for itself
+                    let c = tokens.next().unwrap_or('\\');
+                    stack.push(self.symbol(c));
+                }
+                '.' => {
+                    stack.push(self.any());
+                }
                 c => {
                     stack.push(self.symbol(c));
                 }
@@ -93,7 +121,14 @@ impl Nfa {
         stack
             .into_iter()
             .reduce(|f1, f2| self.concat(f1, f2))
-            .unwrap()
+            .unwrap_or_else(|| self.epsilon())
+    }
+
+    pub fn epsilon(&mut self) -> Fragment {
+        let start = self.new_state(false);
+        let end = self.new_state(true);
+        self.state_mut(start).epsilon_transitions.insert(end);
+        Fragment { start, end }
     }
 
     pub fn symbol(&mut self, c: char) -> Fragment {
@@ -104,6 +139,13 @@ impl Nfa {
         Fragment { start, end }
     }
 
+    pub fn any(&mut self) -> Fragment {
+        let start = self.new_state(false);
+        let end = self.new_state(true);
+        self.state_mut(start).wildcard = Some(end);
+        Fragment { start, end }
+    }
+
     pub fn concat(&mut self, f1: Fragment, f2: Fragment) -> Fragment {
         // Connect the old end state to the new start state
         self.state_mut(f1.end).epsilon_transitions.insert(f2.start);
@@ -140,6 +182,26 @@ impl Nfa {
         Fragment { start, end }
     }
 
+    pub fn plus(&mut self, f: Fragment) -> Fragment {
+        let start = self.new_state(false);
+        let end = self.new_state(true);
+        self.state_mut(start).epsilon_transitions.insert(f.start);
+        self.state_mut(f.end).epsilon_transitions.insert(f.start);
+        self.state_mut(f.end).epsilon_transitions.insert(end);
+        self.state_mut(f.end).accepting = false;
+        Fragment { start, end }
+    }
+
+    pub fn optional(&mut self, f: Fragment) -> Fragment {
+        let start = self.new_state(false);
+        let end = self.new_state(true);
+        self.state_mut(start).epsilon_transitions.insert(f.start);
+        self.state_mut(start).epsilon_transitions.insert(end);
+        self.state_mut(f.end).epsilon_transitions.insert(end);
+        self.state_mut(f.end).accepting = false;
+        Fragment { start, end }
+    }
+
     fn epsilon_closure(&self, start: usize) -> BTreeSet<usize> {
         self.multi_epsilon_closure(vec![start])
     }
@@ -168,7 +230,7 @@ impl Nfa {
             for state in current_states {
                 if let Some(&next_state) = self.state(state).transitions.get(&c) {
                     next_states.extend(self.epsilon_closure(next_state));
-                } else if let Some(&next_state) = self.state(state).transitions.get(&'.') {
+                } else if let Some(next_state) = self.state(state).wildcard {
                     next_states.extend(self.epsilon_closure(next_state));
                 }
             }
@@ -185,8 +247,9 @@ impl Nfa {
 fn insert_explicit_concat_operator(pattern: &str) -> String {
     let mut output = String::new();
     let mut prev_char: Option<char> = None;
+    let mut tokens = pattern.chars();
 
-    for token in pattern.chars() {
+    while let Some(token) = tokens.next() {
         if let Some(prev) = prev_char {
             if !(prev == '(' || prev == '|')
                 && !(token == '*' || token == '?' || token == '+' || token == '|' || token == ')')
@@ -196,6 +259,12 @@ fn insert_explicit_concat_operator(pattern: &str) -> String {
         }
         output.push(token);
         prev_char = Some(token);
+        // An escaped character is a symbol, whatever it is
+        if token == '\\' {
+            if let Some(escaped) = tokens.next() {
+                output.push(escaped);
+            }
+        }
     }
 
     output
@@ -209,9 +278,13 @@ fn to_postfix(pattern: &str) -> String {
         .cloned()
         .collect::<HashMap<char, i32>>();
 
-    for token in pattern.chars() {
+    let mut tokens = pattern.chars();
+    while let Some(token) = tokens.next() {
         // Shunting-yard algorithm
-        if token == '(' {
+        if token == '\\' {
+            output.push(token);
+            output.extend(tokens.next());
+        } else if token == '(' {
             operator_stack.push(token);
         } else if token == ')' {
             while operator_stack.last().map(|&c| c != '(').unwrap_or(false) {
@@ -232,15 +305,15 @@ fn to_postfix(pattern: &str) -> String {
         }
     }
 
-    while !operator_stack.is_empty() {
-        output.push(operator_stack.pop().unwrap());
+    while let Some(operator) = operator_stack.pop() {
+        output.push(operator);
     }
 
     output
 }
 
 #[derive(Debug)]
-struct Regex {
+pub struct Regex {
     states: Nfa,
     start: usize,
 }
@@ -322,6 +395,56 @@ mod tests {
         assert!(!re.matches("aad"));
     }
 
+    #[test]
+    fn test_pattern_plus_optional() {
+        let re = Regex::new("ab+c?");
+        assert!(re.matches("ab"));
+        assert!(re.matches("abc"));
+        assert!(re.matches("abbb"));
+        assert!(re.matches("abbbc"));
+        assert!(!re.matches("a"));
+        assert!(!re.matches("ac"));
+        assert!(!re.matches("abcc"));
+
+        let re = Regex::new("(ab|c)?d");
+        assert!(re.matches("d"));
+        assert!(re.matches("abd"));
+        assert!(re.matches("cd"));
+        assert!(!re.matches("abcd"));
+    }
+
+    #[test]
+    fn test_pattern_empty() {
+        let re = Regex::new("");
+        assert!(re.matches(""));
+        assert!(!re.matches("a"));
+    }
+
+    #[test]
+    fn test_pattern_wildcard() {
+        let re = Regex::new("a.c");
+        assert!(re.matches("abc"));
+        assert!(re.matches("a.c"));
+        assert!(!re.matches("ac"));
+    }
+
+    #[test]
+    fn test_pattern_escape() {
+        let re = Regex::new("\\(a\\|b\\)\\*");
+        assert!(re.matches("(a|b)*"));
+        assert!(!re.matches("a"));
+
+        let re = Regex::new("a\\.c|\\\\+");
+        assert!(re.matches("a.c"));
+        assert!(!re.matches("abc"));
+        assert!(re.matches("\\\\"));
+
+        let re = Regex::new("(\\?|\\+)*");
+        assert!(re.matches(""));
+        assert!(re.matches("?+?"));
+        assert!(!re.matches("a"));
+    }
+
     #[test]
     fn test_fragment_concat_ab() {
         let mut nfa = Nfa::new();
@@ -347,4 +470,36 @@ mod tests {
         assert!(nfa.matches(f3.start, "a"));
         assert!(nfa.matches(f3.start, "b"));
     }
+
+    #[test]
+    fn test_fragment_plus_a() {
+        let mut nfa = Nfa::new();
+        let f1 = nfa.symbol('a');
+        let f2 = nfa.plus(f1);
+
+        assert!(!nfa.matches(f2.start, ""));
+        assert!(nfa.matches(f2.start, "a"));
+        assert!(nfa.matches(f2.start, "aaa"));
+        assert!(!nfa.matches(f2.start, "ab"));
+    }
+
+    #[test]
+    fn test_fragment_optional_a() {
+        let mut nfa = Nfa::new();
+        let f1 = nfa.symbol('a');
+        let f2 = nfa.optional(f1);
+
+        assert!(nfa.matches(f2.start, ""));
+        assert!(nfa.matches(f2.start, "a"));
+        assert!(!nfa.matches(f2.start, "aa"));
+    }
+
+    #[test]
+    fn test_fragment_epsilon() {
+        let mut nfa = Nfa::new();
+        let f = nfa.epsilon();
+
+        assert!(nfa.matches(f.start, ""));
+        assert!(!nfa.matches(f.start, "a"));
+    }
 }